@@ -1,21 +1,36 @@
-use std::fs::read_to_string;
+use std::{env, fs::read_to_string};
 
-fn main() {
-    // load input
-    let input = read_to_string("./input.txt").unwrap();
+mod partition;
 
-    // 1.
-    let mut sums: Vec<u32> = Vec::new();
+fn parse_elves(input: &str) -> Vec<Vec<u32>> {
+    let mut elves: Vec<Vec<u32>> = Vec::new();
     let mut nums: Vec<u32> = Vec::new();
-    input.split("\n").for_each(|line| {
+    input.split('\n').for_each(|line| {
         if let Ok(res) = line.parse::<u32>() {
             nums.push(res);
         } else {
-            let sum: u32 = nums.iter().sum();
-            sums.push(sum);
+            elves.push(nums.clone());
             nums.clear();
         }
     });
+    if !nums.is_empty() {
+        elves.push(nums);
+    }
+    elves
+}
+
+fn main() {
+    // load input
+    let input = read_to_string("./input.txt").unwrap();
+    let elves = parse_elves(&input);
+
+    if env::args().nth(1).as_deref() == Some("redistribute") {
+        redistribute(&elves);
+        return;
+    }
+
+    // 1.
+    let mut sums: Vec<u32> = elves.iter().map(|nums| nums.iter().sum()).collect();
     sums.sort();
     sums.reverse();
     println!("largest: {:?}", sums.first().unwrap());
@@ -24,3 +39,35 @@ fn main() {
     let (top_three, _) = sums.split_at(3);
     println!("sum of top three: {:?}", top_three.iter().sum::<u32>());
 }
+
+fn redistribute(elves: &[Vec<u32>]) {
+    let before: u32 = elves
+        .iter()
+        .map(|nums| nums.iter().sum())
+        .max()
+        .unwrap_or(0);
+    let plan = partition::redistribute(elves);
+
+    println!(
+        "heaviest load: {} -> {} ({})",
+        before,
+        plan.heaviest(),
+        if plan.exact { "optimal" } else { "heuristic" }
+    );
+
+    println!("per-elf totals:");
+    for (elf, total) in plan.totals().iter().enumerate() {
+        println!("  elf {}: {}", elf + 1, total);
+    }
+
+    println!("moves ({}):", plan.moves.len());
+    for m in &plan.moves {
+        println!(
+            "  snack {} ({} calories) from elf {} to elf {}",
+            m.item.index + 1,
+            m.item.calories,
+            m.item.elf + 1,
+            m.to + 1
+        );
+    }
+}
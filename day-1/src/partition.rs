@@ -0,0 +1,311 @@
+// multiway number partitioning of snack items across elves
+//
+// the aim is to minimise the heaviest load any single elf has to carry.
+// small inputs are solved exactly with branch and bound, larger ones, and
+// small ones the search can't settle quickly, use a greedy (largest first)
+// assignment followed by a local search.
+
+use std::cmp::Reverse;
+
+// above this many items the exact search is too slow to be worth it
+const EXACT_LIMIT: usize = 24;
+// search nodes the exact search may visit before giving up on proving its answer
+const NODE_BUDGET: usize = 2_000_000;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Item {
+    pub elf: usize,
+    pub index: usize,
+    pub calories: u32,
+}
+
+#[derive(Debug)]
+pub struct Move {
+    pub item: Item,
+    pub to: usize,
+}
+
+#[derive(Debug)]
+pub struct Plan {
+    pub exact: bool,
+    pub loads: Vec<Vec<Item>>,
+    pub moves: Vec<Move>,
+}
+
+impl Plan {
+    pub fn totals(&self) -> Vec<u32> {
+        self.loads
+            .iter()
+            .map(|load| load.iter().map(|item| item.calories).sum())
+            .collect()
+    }
+
+    pub fn heaviest(&self) -> u32 {
+        self.totals().into_iter().max().unwrap_or(0)
+    }
+}
+
+pub fn redistribute(elves: &[Vec<u32>]) -> Plan {
+    let mut items: Vec<Item> = elves
+        .iter()
+        .enumerate()
+        .flat_map(|(elf, snacks)| {
+            snacks
+                .iter()
+                .enumerate()
+                .map(move |(index, &calories)| Item {
+                    elf,
+                    index,
+                    calories,
+                })
+        })
+        .collect();
+    // largest first works best for both the exact and the greedy search
+    items.sort_by_key(|item| Reverse(item.calories));
+
+    let bins = elves.len();
+    let (assignment, exact) = if items.len() <= EXACT_LIMIT {
+        branch_and_bound(&items, bins)
+    } else {
+        (greedy(&items, bins), false)
+    };
+    // whatever wasn't proven optimal gets polished by the local search, from
+    // the search's best and from the greedy start, keeping the lighter one
+    let heaviest = |assignment: &[usize]| {
+        let mut sums = vec![0u32; bins];
+        for (item, &bin) in items.iter().zip(assignment) {
+            sums[bin] += item.calories;
+        }
+        sums.into_iter().max().unwrap_or(0)
+    };
+    let assignment = if exact {
+        assignment
+    } else {
+        let searched = local_search(&items, assignment, bins);
+        let greedy = local_search(&items, greedy(&items, bins), bins);
+        if heaviest(&greedy) < heaviest(&searched) {
+            greedy
+        } else {
+            searched
+        }
+    };
+    // a heuristic answer that meets the lower bound can't be beaten either
+    let exact = exact || heaviest(&assignment) == lower_bound(&items, bins);
+
+    let mut loads: Vec<Vec<Item>> = vec![vec![]; bins];
+    for (item, bin) in items.iter().zip(assignment) {
+        loads[bin].push(*item);
+    }
+    let loads = match_to_elves(loads, bins);
+
+    let mut moves = vec![];
+    for (to, load) in loads.iter().enumerate() {
+        for item in load {
+            if item.elf != to {
+                moves.push(Move { item: *item, to });
+            }
+        }
+    }
+    moves.sort_by_key(|m| (m.item.elf, m.item.index));
+
+    Plan {
+        exact,
+        loads,
+        moves,
+    }
+}
+
+// nothing can beat the average load or the single biggest item
+fn lower_bound(items: &[Item], bins: usize) -> u32 {
+    let total: u32 = items.iter().map(|item| item.calories).sum();
+    let largest = items.first().map(|item| item.calories).unwrap_or(0);
+    total.div_ceil(bins.max(1) as u32).max(largest)
+}
+
+// the best assignment found, and whether the search finished and so proved it
+// optimal rather than running out of budget
+fn branch_and_bound(items: &[Item], bins: usize) -> (Vec<usize>, bool) {
+    struct Search<'a> {
+        items: &'a [Item],
+        // calories of each item onwards
+        remaining: Vec<u32>,
+        bound: u32,
+        sums: Vec<u32>,
+        current: Vec<usize>,
+        best: Vec<usize>,
+        best_max: u32,
+        nodes: usize,
+    }
+
+    impl Search<'_> {
+        // false once the budget is spent
+        fn run(&mut self, i: usize, current_max: u32) -> bool {
+            if self.best_max == self.bound {
+                return true;
+            }
+            if i == self.items.len() {
+                self.best_max = current_max;
+                self.best = self.current.clone();
+                return true;
+            }
+            // the rest has to fit in the room left below the best so far
+            let room: u64 = self
+                .sums
+                .iter()
+                .map(|&sum| self.best_max.saturating_sub(sum + 1) as u64)
+                .sum();
+            if self.remaining[i] as u64 > room {
+                return true;
+            }
+            self.nodes += 1;
+            if self.nodes > NODE_BUDGET {
+                return false;
+            }
+            let calories = self.items[i].calories;
+            for bin in 0..self.sums.len() {
+                // bins with the same load are interchangeable, only try the first
+                if self.sums[..bin].contains(&self.sums[bin]) {
+                    continue;
+                }
+                let sum = self.sums[bin] + calories;
+                if sum >= self.best_max {
+                    continue;
+                }
+                self.sums[bin] = sum;
+                self.current[i] = bin;
+                let finished = self.run(i + 1, current_max.max(sum));
+                self.sums[bin] -= calories;
+                if !finished {
+                    return false;
+                }
+            }
+            true
+        }
+    }
+
+    // the greedy answer gives a starting bound to prune against
+    let initial = greedy(items, bins);
+    let mut sums = vec![0; bins];
+    for (item, &bin) in items.iter().zip(&initial) {
+        sums[bin] += item.calories;
+    }
+    let mut remaining = vec![0; items.len() + 1];
+    for i in (0..items.len()).rev() {
+        remaining[i] = remaining[i + 1] + items[i].calories;
+    }
+    let mut search = Search {
+        items,
+        remaining,
+        bound: lower_bound(items, bins),
+        sums: vec![0; bins],
+        current: vec![0; items.len()],
+        best_max: sums.into_iter().max().unwrap_or(0),
+        best: initial,
+        nodes: 0,
+    };
+    let finished = search.run(0, 0);
+    (search.best, finished)
+}
+
+// largest processing time first: each item goes to the lightest elf
+fn greedy(items: &[Item], bins: usize) -> Vec<usize> {
+    let mut sums = vec![0u32; bins];
+    items
+        .iter()
+        .map(|item| {
+            let (bin, _) = sums.iter().enumerate().min_by_key(|(_, &sum)| sum).unwrap();
+            sums[bin] += item.calories;
+            bin
+        })
+        .collect()
+}
+
+// repeatedly move or swap items out of the heaviest bin while that lowers
+// the larger of the two bins involved. every step strictly shrinks the sum
+// of squared loads, so this always terminates.
+fn local_search(items: &[Item], mut assignment: Vec<usize>, bins: usize) -> Vec<usize> {
+    let mut sums = vec![0u32; bins];
+    for (item, &bin) in items.iter().zip(&assignment) {
+        sums[bin] += item.calories;
+    }
+
+    loop {
+        let (heaviest, &max) = sums.iter().enumerate().max_by_key(|(_, &sum)| sum).unwrap();
+        let mut improved = false;
+
+        'search: for a in 0..items.len() {
+            if assignment[a] != heaviest {
+                continue;
+            }
+            let a_cal = items[a].calories;
+
+            // move a single item to another bin
+            for bin in 0..bins {
+                if bin != heaviest && sums[bin] + a_cal < max {
+                    sums[heaviest] -= a_cal;
+                    sums[bin] += a_cal;
+                    assignment[a] = bin;
+                    improved = true;
+                    break 'search;
+                }
+            }
+
+            // swap it with a smaller item from another bin
+            for b in 0..items.len() {
+                let bin = assignment[b];
+                let b_cal = items[b].calories;
+                if bin == heaviest || b_cal >= a_cal {
+                    continue;
+                }
+                if sums[bin] + a_cal - b_cal < max {
+                    sums[heaviest] = sums[heaviest] - a_cal + b_cal;
+                    sums[bin] = sums[bin] + a_cal - b_cal;
+                    assignment.swap(a, b);
+                    improved = true;
+                    break 'search;
+                }
+            }
+        }
+
+        if !improved {
+            return assignment;
+        }
+    }
+}
+
+// the bins come out unordered, so hand each one to the elf that already
+// holds most of its items to keep the number of moves down
+fn match_to_elves(loads: Vec<Vec<Item>>, elves: usize) -> Vec<Vec<Item>> {
+    let mut pairs: Vec<(usize, usize, usize)> = vec![];
+    for (bin, load) in loads.iter().enumerate() {
+        let mut counts = vec![0; elves];
+        for item in load {
+            counts[item.elf] += 1;
+        }
+        for (elf, count) in counts.into_iter().enumerate() {
+            if count > 0 {
+                pairs.push((count, bin, elf));
+            }
+        }
+    }
+    pairs.sort_by_key(|&(count, _, _)| Reverse(count));
+
+    let mut owner: Vec<Option<usize>> = vec![None; loads.len()];
+    let mut taken = vec![false; elves];
+    for (_, bin, elf) in pairs {
+        if owner[bin].is_none() && !taken[elf] {
+            owner[bin] = Some(elf);
+            taken[elf] = true;
+        }
+    }
+    let mut free = (0..elves).filter(|&elf| !taken[elf]);
+    for slot in owner.iter_mut().filter(|slot| slot.is_none()) {
+        *slot = free.next();
+    }
+
+    let mut result = vec![vec![]; elves];
+    for (load, elf) in loads.into_iter().zip(owner) {
+        result[elf.unwrap()] = load;
+    }
+    result
+}
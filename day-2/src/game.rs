@@ -0,0 +1,214 @@
+use std::{collections::HashMap, fmt, fs::read_to_string};
+
+// index of a shape in the game's cycle
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Shape(pub usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Strat {
+    Win,
+    Draw,
+    Lose,
+}
+
+impl TryFrom<&str> for Strat {
+    type Error = &'static str;
+
+    fn try_from(word: &str) -> Result<Self, Self::Error> {
+        match word {
+            "lose" => Ok(Strat::Lose),
+            "draw" => Ok(Strat::Draw),
+            "win" => Ok(Strat::Win),
+            _ => Err("Cannot be converted to Strat"),
+        }
+    }
+}
+
+impl fmt::Display for Strat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let word = match self {
+            Strat::Lose => "lose",
+            Strat::Draw => "draw",
+            Strat::Win => "win",
+        };
+        write!(f, "{}", word)
+    }
+}
+
+// a game of any odd number of shapes arranged in a cycle. a shape beats
+// another when it sits an odd number of places after it (wrapping round),
+// so every shape beats exactly half of the others. a second column symbol
+// can have a player reading, a strategy reading or both.
+#[derive(Debug)]
+pub struct Game {
+    names: Vec<String>,
+    scores: Vec<u32>,
+    outcome_scores: HashMap<Strat, u32>,
+    opponent: HashMap<String, Shape>,
+    player: HashMap<String, Shape>,
+    strategy: HashMap<String, Strat>,
+}
+
+impl Game {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let config = read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::parse(&config).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn parse(config: &str) -> Result<Self, String> {
+        let mut game = Game {
+            names: vec![],
+            scores: vec![],
+            outcome_scores: HashMap::new(),
+            opponent: HashMap::new(),
+            player: HashMap::new(),
+            strategy: HashMap::new(),
+        };
+
+        // symbols can refer to shapes declared further down, so resolve them at the end
+        let mut symbols: Vec<(usize, &str, &str, &str)> = vec![];
+
+        for (index, line) in config.lines().enumerate() {
+            let number = index + 1;
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [key, first, second] = parts[..] else {
+                return Err(format!("line {}: expected '<key> <name> <value>'", number));
+            };
+            match key {
+                "shape" => {
+                    if game.names.iter().any(|name| name == first) {
+                        return Err(format!("line {}: shape {} declared twice", number, first));
+                    }
+                    let score = second
+                        .parse()
+                        .map_err(|_| format!("line {}: invalid score '{}'", number, second))?;
+                    game.names.push(first.to_string());
+                    game.scores.push(score);
+                }
+                "outcome" => {
+                    let strat =
+                        Strat::try_from(first).map_err(|e| format!("line {}: {}", number, e))?;
+                    let score = second
+                        .parse()
+                        .map_err(|_| format!("line {}: invalid score '{}'", number, second))?;
+                    game.outcome_scores.insert(strat, score);
+                }
+                "opponent" | "player" | "strategy" => symbols.push((number, key, first, second)),
+                _ => return Err(format!("line {}: unknown key '{}'", number, key)),
+            }
+        }
+
+        if game.names.len() < 3 || game.names.len().is_multiple_of(2) {
+            return Err(format!(
+                "a cyclic game needs an odd number of shapes, at least 3, found {}",
+                game.names.len()
+            ));
+        }
+        for strat in [Strat::Lose, Strat::Draw, Strat::Win] {
+            if !game.outcome_scores.contains_key(&strat) {
+                return Err(format!("missing score for outcome {:?}", strat));
+            }
+        }
+
+        for (number, key, symbol, value) in symbols {
+            let duplicate = match key {
+                "strategy" => {
+                    let strat =
+                        Strat::try_from(value).map_err(|e| format!("line {}: {}", number, e))?;
+                    game.strategy.insert(symbol.to_string(), strat).is_some()
+                }
+                _ => {
+                    let shape = game
+                        .shape_named(value)
+                        .ok_or_else(|| format!("line {}: unknown shape '{}'", number, value))?;
+                    let map = if key == "opponent" {
+                        &mut game.opponent
+                    } else {
+                        &mut game.player
+                    };
+                    map.insert(symbol.to_string(), shape).is_some()
+                }
+            };
+            if duplicate {
+                return Err(format!(
+                    "line {}: {} symbol {} mapped twice",
+                    number, key, symbol
+                ));
+            }
+        }
+
+        Ok(game)
+    }
+
+    fn shape_named(&self, name: &str) -> Option<Shape> {
+        self.names.iter().position(|n| n == name).map(Shape)
+    }
+
+    pub fn shapes(&self) -> impl DoubleEndedIterator<Item = Shape> {
+        (0..self.names.len()).map(Shape)
+    }
+
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.names[shape.0]
+    }
+
+    pub fn opponent_shape(&self, symbol: &str) -> Result<Shape, &'static str> {
+        self.opponent
+            .get(symbol)
+            .copied()
+            .ok_or("Cannot be converted to Shape")
+    }
+
+    pub fn player_shape(&self, symbol: &str) -> Result<Shape, &'static str> {
+        self.player
+            .get(symbol)
+            .copied()
+            .ok_or("Cannot be converted to Shape")
+    }
+
+    pub fn strat(&self, symbol: &str) -> Result<Strat, &'static str> {
+        self.strategy
+            .get(symbol)
+            .copied()
+            .ok_or("Cannot be converted to Strat")
+    }
+
+    // how the round ends for the player of `second`
+    pub fn outcome(&self, first: Shape, second: Shape) -> Strat {
+        let n = self.names.len();
+        let distance = (second.0 + n - first.0) % n;
+        if distance == 0 {
+            Strat::Draw
+        } else if distance % 2 == 1 {
+            Strat::Win
+        } else {
+            Strat::Lose
+        }
+    }
+
+    pub fn shape_score(&self, shape: Shape) -> u32 {
+        self.scores[shape.0]
+    }
+
+    pub fn outcome_score(&self, strat: Strat) -> u32 {
+        self.outcome_scores[&strat]
+    }
+
+    pub fn get_score(&self, first: Shape, second: Shape) -> u32 {
+        self.shape_score(second) + self.outcome_score(self.outcome(first, second))
+    }
+
+    pub fn get_correct_shape(&self, first: Shape, strat: Strat) -> Shape {
+        let n = self.names.len();
+        match strat {
+            Strat::Draw => first,
+            // one step forward is an odd distance, one step back is even
+            Strat::Win => Shape((first.0 + 1) % n),
+            Strat::Lose => Shape((first.0 + n - 1) % n),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+use crate::game::{Game, Shape, Strat};
+
+// one line of the strategy guide, decoded under both readings of the second
+// column. a symbol the game only gives one reading leaves the other `None`.
+#[derive(Clone, Copy, Debug)]
+pub struct Round<'a> {
+    pub line: usize,
+    pub opponent: Shape,
+    pub symbol: &'a str,
+    pub guessed: Option<Shape>,
+    pub strat: Option<Strat>,
+}
+
+#[derive(Debug)]
+pub struct LineError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+// decodes every line it can, collecting the rest as errors rather than giving up
+pub fn parse<'a>(game: &Game, input: &'a str) -> (Vec<Round<'a>>, Vec<LineError>) {
+    let mut rounds = vec![];
+    let mut errors = vec![];
+
+    for (index, text) in input.lines().enumerate() {
+        let line = index + 1;
+        if text.trim().is_empty() {
+            continue;
+        }
+        match parse_round(game, text, line) {
+            Ok(round) => rounds.push(round),
+            Err(message) => errors.push(LineError { line, message }),
+        }
+    }
+
+    (rounds, errors)
+}
+
+fn parse_round<'a>(game: &Game, text: &'a str, line: usize) -> Result<Round<'a>, String> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let [first, symbol] = tokens[..] else {
+        return Err(format!("expected two symbols, found {}", tokens.len()));
+    };
+    let opponent = game
+        .opponent_shape(first)
+        .map_err(|e| format!("{}: '{}'", e, first))?;
+    let guessed = game.player_shape(symbol).ok();
+    let strat = game.strat(symbol).ok();
+    if guessed.is_none() && strat.is_none() {
+        return Err(format!(
+            "'{}' is neither a shape nor an outcome in this game",
+            symbol
+        ));
+    }
+    Ok(Round {
+        line,
+        opponent,
+        symbol,
+        guessed,
+        strat,
+    })
+}
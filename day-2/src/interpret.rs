@@ -0,0 +1,141 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::{
+    game::{Game, Shape, Strat},
+    guide::Round,
+};
+
+// what a second column symbol could stand for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Meaning {
+    Shape(Shape),
+    Strat(Strat),
+}
+
+#[derive(Debug)]
+pub struct Interpretation<'a> {
+    pub symbols: Vec<(&'a str, Meaning)>,
+    pub score: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Interpretation<'_> {
+    pub fn kind(&self) -> &'static str {
+        let shapes = self
+            .symbols
+            .iter()
+            .filter(|(_, meaning)| matches!(meaning, Meaning::Shape(_)))
+            .count();
+        if shapes == self.symbols.len() {
+            "shapes"
+        } else if shapes == 0 {
+            "outcomes"
+        } else {
+            "mixed"
+        }
+    }
+
+    // the puzzle's own readings, as given in the game config
+    pub fn is_guessed(&self, game: &Game) -> bool {
+        self.symbols
+            .iter()
+            .all(|&(symbol, meaning)| game.player_shape(symbol).map(Meaning::Shape) == Ok(meaning))
+    }
+
+    pub fn is_correct(&self, game: &Game) -> bool {
+        self.symbols
+            .iter()
+            .all(|&(symbol, meaning)| game.strat(symbol).map(Meaning::Strat) == Ok(meaning))
+    }
+
+    pub fn describe(&self, game: &Game) -> String {
+        let parts: Vec<String> = self
+            .symbols
+            .iter()
+            .map(|(symbol, meaning)| match meaning {
+                Meaning::Shape(shape) => format!("{}={}", symbol, game.name(*shape)),
+                Meaning::Strat(strat) => format!("{}={}", symbol, strat),
+            })
+            .collect();
+        parts.join(" ")
+    }
+}
+
+// score the guide under every way of giving each second column symbol its
+// own shape or outcome, best first
+pub fn rank<'a>(game: &Game, rounds: &[Round<'a>]) -> Vec<Interpretation<'a>> {
+    // the guide only matters through how often each pairing comes up
+    let mut counts: HashMap<(Shape, &str), u32> = HashMap::new();
+    for round in rounds {
+        *counts.entry((round.opponent, round.symbol)).or_insert(0) += 1;
+    }
+    let mut symbols: Vec<&str> = rounds.iter().map(|round| round.symbol).collect();
+    symbols.sort();
+    symbols.dedup();
+
+    let meanings: Vec<Meaning> = game
+        .shapes()
+        .map(Meaning::Shape)
+        .chain([Strat::Lose, Strat::Draw, Strat::Win].map(Meaning::Strat))
+        .collect();
+
+    let mut results = vec![];
+    let mut score = |chosen: &[Meaning]| {
+        let mut result = Interpretation {
+            symbols: symbols
+                .iter()
+                .copied()
+                .zip(chosen.iter().copied())
+                .collect(),
+            score: 0,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        };
+        for (&(first, second), &count) in &counts {
+            let index = symbols.iter().position(|&s| s == second).unwrap();
+            let played = match chosen[index] {
+                Meaning::Shape(shape) => shape,
+                Meaning::Strat(strat) => game.get_correct_shape(first, strat),
+            };
+            result.score += count * game.get_score(first, played);
+            match game.outcome(first, played) {
+                Strat::Win => result.wins += count,
+                Strat::Draw => result.draws += count,
+                Strat::Lose => result.losses += count,
+            }
+        }
+        results.push(result);
+    };
+    let mut used = vec![false; meanings.len()];
+    enumerate(&meanings, symbols.len(), &mut used, &mut vec![], &mut score);
+
+    results.sort_by_key(|result| Reverse(result.score));
+    results
+}
+
+// every ordered choice of `len` distinct meanings
+fn enumerate(
+    meanings: &[Meaning],
+    len: usize,
+    used: &mut [bool],
+    chosen: &mut Vec<Meaning>,
+    visit: &mut dyn FnMut(&[Meaning]),
+) {
+    if chosen.len() == len {
+        visit(chosen);
+        return;
+    }
+    for i in 0..meanings.len() {
+        if used[i] {
+            continue;
+        }
+        used[i] = true;
+        chosen.push(meanings[i]);
+        enumerate(meanings, len, used, chosen, visit);
+        chosen.pop();
+        used[i] = false;
+    }
+}
@@ -1,100 +1,248 @@
-use std::fs::read_to_string;
+use std::{env, fs::read_to_string};
 
-enum Shape {
-    Rock,
-    Paper,
-    Scissors,
-}
+mod game;
+mod guide;
+mod interpret;
+mod simulate;
 
-impl TryFrom<&str> for Shape {
-    type Error = &'static str;
-
-    fn try_from(char: &str) -> Result<Self, Self::Error> {
-        match char {
-            "A" => Ok(Shape::Rock),
-            "B" => Ok(Shape::Paper),
-            "C" => Ok(Shape::Scissors),
-            "X" => Ok(Shape::Rock),
-            "Y" => Ok(Shape::Paper),
-            "Z" => Ok(Shape::Scissors),
-            _ => Err("Cannot be converted to Shape"),
-        }
-    }
-}
+use game::{Game, Shape};
+use guide::Round;
+use simulate::{Fixed, Frequency, Guide, Markov, Player};
 
-enum Strat {
-    Win,
-    Draw,
-    Lose,
-}
-
-impl TryFrom<&str> for Strat {
-    type Error = &'static str;
-
-    fn try_from(char: &str) -> Result<Self, Self::Error> {
-        match char {
-            "X" => Ok(Strat::Lose),
-            "Y" => Ok(Strat::Draw),
-            "Z" => Ok(Strat::Win),
-            _ => Err("Cannot be converted to Strat"),
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let game_path = option(&args, "--game").unwrap_or("./rps.txt");
+    let game = match Game::load(game_path) {
+        Ok(game) => game,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
-    }
-}
+    };
 
-fn main() {
     // load input
     let input = read_to_string("./input.txt").unwrap();
 
-    let turns: Vec<&str> = input.split("\n").filter(|line| line.len() > 0).collect();
+    let (rounds, errors) = guide::parse(&game, &input);
+    for error in &errors {
+        eprintln!("skipping {}", error);
+    }
 
-    let mut guessed_strat_score = 0;
-    let mut correct_strat_score = 0;
+    match command(&args) {
+        Some("interpretations") => {
+            let top = option(&args, "--top").map(|top| top.parse().unwrap());
+            interpretations(&game, &rounds, top);
+        }
+        Some("simulate") => {
+            let order = option(&args, "--order").map_or(3, |order| order.parse().unwrap());
+            simulate(&game, &rounds, order);
+        }
+        Some("report") => report(&game, &rounds, args.iter().any(|arg| arg == "--csv")),
+        _ => {
+            let mut guessed_strat_score = 0;
+            let mut correct_strat_score = 0;
+
+            for round in rounds.iter() {
+                if let Some(guessed) = round.guessed {
+                    guessed_strat_score += game.get_score(round.opponent, guessed);
+                }
+                if let Some(strat) = round.strat {
+                    let correct_second_shape = game.get_correct_shape(round.opponent, strat);
+                    correct_strat_score += game.get_score(round.opponent, correct_second_shape);
+                }
+            }
+
+            println!("Guessed strat score: {}", guessed_strat_score);
+            println!("Correct strat score: {}", correct_strat_score);
+
+            let unread = |reading: &str, count: usize| {
+                if count > 0 {
+                    eprintln!("{} round(s) with no {} reading left out", count, reading);
+                }
+            };
+            unread(
+                "shape",
+                rounds.iter().filter(|r| r.guessed.is_none()).count(),
+            );
+            unread(
+                "strategy",
+                rounds.iter().filter(|r| r.strat.is_none()).count(),
+            );
+        }
+    }
 
-    for turn in turns.iter() {
-        let turn: Vec<&str> = turn.to_owned().split(" ").collect();
-        let [first, second] = [
-            turn.get(0).unwrap().to_owned(),
-            turn.get(1).unwrap().to_owned(),
-        ];
-        let first_shape = Shape::try_from(first).unwrap();
-        let guessed_second_shape = Shape::try_from(second).unwrap();
+    if !errors.is_empty() {
+        eprintln!("{} malformed line(s) skipped", errors.len());
+    }
+}
 
-        guessed_strat_score += get_score(&first_shape, guessed_second_shape);
+fn interpretations(game: &Game, rounds: &[Round], top: Option<usize>) {
+    let ranked = interpret::rank(game, rounds);
+    println!(
+        "{:>5} {:>7} {:>6} {:>6} {:>6}  {:<8}  reading",
+        "rank", "score", "wins", "draws", "losses", "kind"
+    );
+    for (index, result) in ranked.iter().enumerate().take(top.unwrap_or(ranked.len())) {
+        let mut reading = result.describe(game);
+        if result.is_guessed(game) {
+            reading.push_str("  (guessed strat)");
+        }
+        if result.is_correct(game) {
+            reading.push_str("  (correct strat)");
+        }
+        println!(
+            "{:>5} {:>7} {:>6} {:>6} {:>6}  {:<8}  {}",
+            index + 1,
+            result.score,
+            result.wins,
+            result.draws,
+            result.losses,
+            result.kind(),
+            reading
+        );
+    }
+}
 
-        let strat = Strat::try_from(second).unwrap();
-        let correct_second_shape = get_correct_shape(&first_shape, strat);
+fn simulate(game: &Game, rounds: &[Round], order: usize) {
+    let opponent: Vec<Shape> = rounds.iter().map(|round| round.opponent).collect();
 
-        correct_strat_score += get_score(&first_shape, correct_second_shape);
+    let mut players: Vec<Box<dyn Player>> = vec![];
+    for shape in game.shapes() {
+        players.push(Box::new(Fixed {
+            shape,
+            label: game.name(shape).to_string(),
+        }));
+    }
+    // the guide only plays if every round has the reading it follows
+    let guessed: Option<Vec<Shape>> = rounds.iter().map(|round| round.guessed).collect();
+    let correct: Option<Vec<Shape>> = rounds
+        .iter()
+        .map(|round| Some(game.get_correct_shape(round.opponent, round.strat?)))
+        .collect();
+    for (label, moves) in [("guessed", guessed), ("correct", correct)] {
+        match moves {
+            Some(moves) => players.push(Box::new(Guide { label, moves })),
+            None => eprintln!(
+                "some rounds have no {} reading, leaving the guide out",
+                label
+            ),
+        }
+    }
+    players.push(Box::new(Frequency::new()));
+    for k in 1..=order {
+        players.push(Box::new(Markov::new(k)));
     }
 
-    println!("Guessed strat score: {}", guessed_strat_score);
-    println!("Correct strat score: {}", correct_strat_score);
+    println!(
+        "{:<18} {:>7} {:>6} {:>6} {:>6} {:>9}",
+        "strategy", "score", "wins", "draws", "losses", "win rate"
+    );
+    for standing in simulate::run(game, &opponent, players) {
+        println!(
+            "{:<18} {:>7} {:>6} {:>6} {:>6} {:>8.1}%",
+            standing.name,
+            standing.score,
+            standing.wins,
+            standing.draws,
+            standing.losses,
+            standing.win_rate() * 100.0
+        );
+    }
 }
 
-fn get_score(first: &Shape, second: Shape) -> u32 {
-    return match (first, second) {
-        (Shape::Rock, Shape::Rock) => 1 + 3,
-        (Shape::Rock, Shape::Paper) => 2 + 6,
-        (Shape::Rock, Shape::Scissors) => 3 + 0,
-        (Shape::Paper, Shape::Rock) => 1 + 0,
-        (Shape::Paper, Shape::Paper) => 2 + 3,
-        (Shape::Paper, Shape::Scissors) => 3 + 6,
-        (Shape::Scissors, Shape::Rock) => 1 + 6,
-        (Shape::Scissors, Shape::Paper) => 2 + 0,
-        (Shape::Scissors, Shape::Scissors) => 3 + 3,
+// every round scored under both readings, as an aligned table or as csv
+fn report(game: &Game, rounds: &[Round], csv: bool) {
+    let header = [
+        "line",
+        "opponent",
+        "guessed shape",
+        "guessed outcome",
+        "guessed shape points",
+        "guessed outcome points",
+        "strat",
+        "correct shape",
+        "correct outcome",
+        "correct shape points",
+        "correct outcome points",
+    ];
+    let rows: Vec<[String; 11]> = rounds
+        .iter()
+        .map(|round| {
+            // a reading the round doesn't have is left as '-'
+            let missing = || String::from("-");
+            let mut row: [String; 11] = std::array::from_fn(|_| missing());
+            row[0] = round.line.to_string();
+            row[1] = game.name(round.opponent).to_string();
+            if let Some(guessed) = round.guessed {
+                let guessed_outcome = game.outcome(round.opponent, guessed);
+                row[2] = game.name(guessed).to_string();
+                row[3] = guessed_outcome.to_string();
+                row[4] = game.shape_score(guessed).to_string();
+                row[5] = game.outcome_score(guessed_outcome).to_string();
+            }
+            if let Some(strat) = round.strat {
+                let correct = game.get_correct_shape(round.opponent, strat);
+                let correct_outcome = game.outcome(round.opponent, correct);
+                row[6] = strat.to_string();
+                row[7] = game.name(correct).to_string();
+                row[8] = correct_outcome.to_string();
+                row[9] = game.shape_score(correct).to_string();
+                row[10] = game.outcome_score(correct_outcome).to_string();
+            }
+            row
+        })
+        .collect();
+
+    if csv {
+        println!("{}", header.join(","));
+        for row in rows {
+            println!("{}", row.join(","));
+        }
+        return;
+    }
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .fold(header[i].len(), usize::max)
+        })
+        .collect();
+    let print_row = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:>width$}", cell, width = width))
+            .collect();
+        println!("{}", padded.join("  "));
     };
+    print_row(header.to_vec());
+    for row in &rows {
+        print_row(row.iter().map(|cell| cell.as_str()).collect());
+    }
 }
 
-fn get_correct_shape(first_shape: &Shape, strat: Strat) -> Shape {
-    return match (first_shape, strat) {
-        (Shape::Rock, Strat::Win) => Shape::Paper,
-        (Shape::Rock, Strat::Draw) => Shape::Rock,
-        (Shape::Rock, Strat::Lose) => Shape::Scissors,
-        (Shape::Paper, Strat::Win) => Shape::Scissors,
-        (Shape::Paper, Strat::Draw) => Shape::Paper,
-        (Shape::Paper, Strat::Lose) => Shape::Rock,
-        (Shape::Scissors, Strat::Win) => Shape::Rock,
-        (Shape::Scissors, Strat::Draw) => Shape::Scissors,
-        (Shape::Scissors, Strat::Lose) => Shape::Paper,
-    };
+// flags that stand alone rather than taking a value
+const SWITCHES: [&str; 1] = ["--csv"];
+
+// first argument that isn't a `--flag` or its value
+fn command(args: &[String]) -> Option<&str> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--") {
+            if !SWITCHES.contains(&arg.as_str()) {
+                args.next();
+            }
+        } else {
+            return Some(arg);
+        }
+    }
+    None
+}
+
+fn option<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|value| value.as_str())
 }
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use crate::game::{Game, Shape, Strat};
+
+// a player strategy, shown the opponent's moves one round at a time
+pub trait Player {
+    fn name(&self) -> String;
+    fn choose(&mut self, game: &Game, round: usize) -> Shape;
+    fn observe(&mut self, _opponent: Shape) {}
+}
+
+// always plays the same shape
+pub struct Fixed {
+    pub shape: Shape,
+    pub label: String,
+}
+
+impl Player for Fixed {
+    fn name(&self) -> String {
+        format!("always {}", self.label)
+    }
+
+    fn choose(&mut self, _game: &Game, _round: usize) -> Shape {
+        self.shape
+    }
+}
+
+// plays whatever the strategy guide says for each round
+pub struct Guide {
+    pub label: &'static str,
+    pub moves: Vec<Shape>,
+}
+
+impl Player for Guide {
+    fn name(&self) -> String {
+        format!("guide ({})", self.label)
+    }
+
+    fn choose(&mut self, _game: &Game, round: usize) -> Shape {
+        self.moves[round]
+    }
+}
+
+// beats the shape the opponent has played most so far
+pub struct Frequency {
+    counts: HashMap<Shape, u32>,
+}
+
+impl Frequency {
+    pub fn new() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+}
+
+impl Player for Frequency {
+    fn name(&self) -> String {
+        String::from("frequency")
+    }
+
+    fn choose(&mut self, game: &Game, _round: usize) -> Shape {
+        counter(game, &self.counts)
+    }
+
+    fn observe(&mut self, opponent: Shape) {
+        *self.counts.entry(opponent).or_insert(0) += 1;
+    }
+}
+
+// beats the shape that most often followed the opponent's last k moves,
+// falling back to overall frequency until that context has been seen
+pub struct Markov {
+    order: usize,
+    history: Vec<Shape>,
+    overall: HashMap<Shape, u32>,
+    table: HashMap<Vec<Shape>, HashMap<Shape, u32>>,
+}
+
+impl Markov {
+    pub fn new(order: usize) -> Self {
+        Self {
+            order,
+            history: vec![],
+            overall: HashMap::new(),
+            table: HashMap::new(),
+        }
+    }
+
+    fn context(&self) -> Option<&[Shape]> {
+        let len = self.history.len();
+        (len >= self.order).then(|| &self.history[len - self.order..])
+    }
+}
+
+impl Player for Markov {
+    fn name(&self) -> String {
+        format!("markov (k = {})", self.order)
+    }
+
+    fn choose(&mut self, game: &Game, _round: usize) -> Shape {
+        match self.context().and_then(|context| self.table.get(context)) {
+            Some(counts) => counter(game, counts),
+            None => counter(game, &self.overall),
+        }
+    }
+
+    fn observe(&mut self, opponent: Shape) {
+        if let Some(context) = self.context() {
+            let counts = self.table.entry(context.to_vec()).or_default();
+            *counts.entry(opponent).or_insert(0) += 1;
+        }
+        *self.overall.entry(opponent).or_insert(0) += 1;
+        self.history.push(opponent);
+    }
+}
+
+// the shape that beats the most likely next move, earliest shape on ties
+fn counter(game: &Game, counts: &HashMap<Shape, u32>) -> Shape {
+    let predicted = game
+        .shapes()
+        .rev()
+        .max_by_key(|shape| counts.get(shape).copied().unwrap_or(0))
+        .unwrap();
+    game.get_correct_shape(predicted, Strat::Win)
+}
+
+#[derive(Debug, Default)]
+pub struct Standing {
+    pub name: String,
+    pub score: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Standing {
+    pub fn win_rate(&self) -> f64 {
+        let rounds = self.wins + self.draws + self.losses;
+        if rounds == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / rounds as f64
+    }
+}
+
+// plays every strategy against the opponent's sequence of moves
+pub fn run(game: &Game, opponent: &[Shape], players: Vec<Box<dyn Player>>) -> Vec<Standing> {
+    players
+        .into_iter()
+        .map(|mut player| {
+            let mut standing = Standing {
+                name: player.name(),
+                ..Default::default()
+            };
+            for (round, &first) in opponent.iter().enumerate() {
+                let second = player.choose(game, round);
+                standing.score += game.get_score(first, second);
+                match game.outcome(first, second) {
+                    Strat::Win => standing.wins += 1,
+                    Strat::Draw => standing.draws += 1,
+                    Strat::Lose => standing.losses += 1,
+                }
+                player.observe(first);
+            }
+            standing
+        })
+        .collect()
+}
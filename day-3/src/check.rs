@@ -0,0 +1,107 @@
+use std::fmt;
+
+use crate::{badge_candidates, items::ItemSet, Backpack};
+
+#[derive(Debug)]
+pub enum Problem {
+    OddLength {
+        line: usize,
+        len: usize,
+    },
+    SharedItems {
+        line: usize,
+        items: ItemSet,
+    },
+    Badges {
+        lines: (usize, usize),
+        items: ItemSet,
+    },
+    IncompleteGroup {
+        lines: (usize, usize),
+        len: usize,
+        size: usize,
+    },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::OddLength { line, len } => write!(
+                f,
+                "line {}: {} items can't be split into two equal compartments",
+                line, len
+            ),
+            Problem::SharedItems { line, items } => write!(
+                f,
+                "line {}: expected one item in both compartments, found {}{}",
+                line,
+                items.len(),
+                describe(items)
+            ),
+            Problem::Badges { lines, items } => write!(
+                f,
+                "lines {}-{}: expected one badge for the group, found {}{}",
+                lines.0,
+                lines.1,
+                items.len(),
+                describe(items)
+            ),
+            Problem::IncompleteGroup { lines, len, size } => write!(
+                f,
+                "lines {}-{}: trailing group has {} backpack(s), expected {}",
+                lines.0, lines.1, len, size
+            ),
+        }
+    }
+}
+
+fn describe(items: &ItemSet) -> String {
+    if items.is_empty() {
+        return String::new();
+    }
+    format!(" ({})", items.iter().collect::<String>())
+}
+
+// everything about the backpacks that the puzzle takes for granted
+pub fn check(backpacks: &[Backpack], group_size: usize) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    for backpack in backpacks {
+        let len = backpack.first_compartment.chars().count()
+            + backpack.second_compartment.chars().count();
+        if !len.is_multiple_of(2) {
+            problems.push(Problem::OddLength {
+                line: backpack.line,
+                len,
+            });
+        }
+        let shared = backpack.shared_items();
+        if shared.len() != 1 {
+            problems.push(Problem::SharedItems {
+                line: backpack.line,
+                items: shared,
+            });
+        }
+    }
+
+    for group in backpacks.chunks(group_size) {
+        let lines = (group[0].line, group[group.len() - 1].line);
+        if group.len() < group_size {
+            problems.push(Problem::IncompleteGroup {
+                lines,
+                len: group.len(),
+                size: group_size,
+            });
+            continue;
+        }
+        let badges = badge_candidates(group);
+        if badges.len() != 1 {
+            problems.push(Problem::Badges {
+                lines,
+                items: badges,
+            });
+        }
+    }
+
+    problems
+}
@@ -0,0 +1,86 @@
+// a set of rucksack items. a-z and A-Z are packed into the low 52 bits of
+// a u64 (a-z are bits 0-25, A-Z are bits 26-51) so the puzzle's own items
+// never allocate, any other unicode item is kept in a small sorted list.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemSet {
+    mask: u64,
+    other: Vec<char>,
+}
+
+impl ItemSet {
+    fn bit(item: char) -> Option<u32> {
+        match item {
+            'a'..='z' => Some(item as u32 - 'a' as u32),
+            'A'..='Z' => Some(item as u32 - 'A' as u32 + 26),
+            _ => None,
+        }
+    }
+
+    fn item(bit: u32) -> char {
+        if bit < 26 {
+            (b'a' + bit as u8) as char
+        } else {
+            (b'A' + (bit - 26) as u8) as char
+        }
+    }
+
+    pub fn from_items(items: &str) -> Self {
+        let mut set = ItemSet::default();
+        for item in items.chars() {
+            match Self::bit(item) {
+                Some(bit) => set.mask |= 1 << bit,
+                None => set.other.push(item),
+            }
+        }
+        set.other.sort();
+        set.other.dedup();
+        set
+    }
+
+    pub fn intersection(&self, other: &ItemSet) -> ItemSet {
+        ItemSet {
+            mask: self.mask & other.mask,
+            other: self
+                .other
+                .iter()
+                .filter(|item| other.other.binary_search(item).is_ok())
+                .copied()
+                .collect(),
+        }
+    }
+
+    pub fn union(&self, other: &ItemSet) -> ItemSet {
+        let mut items: Vec<char> = self.other.iter().chain(&other.other).copied().collect();
+        items.sort();
+        items.dedup();
+        ItemSet {
+            mask: self.mask | other.mask,
+            other: items,
+        }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.mask.count_ones() + self.other.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mask == 0 && self.other.is_empty()
+    }
+
+    pub fn first(&self) -> Option<char> {
+        self.iter().next()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = char> + '_ {
+        let mut bits = self.mask;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let bit = bits.trailing_zeros();
+            bits &= bits - 1;
+            Some(Self::item(bit))
+        })
+        .chain(self.other.iter().copied())
+    }
+}
@@ -1,103 +1,208 @@
-use std::{collections::HashSet, fs::read_to_string};
+use std::{env, fs::read_to_string};
+
+mod check;
+mod items;
+mod priority;
+mod reorganise;
+
+use items::ItemSet;
+use priority::{Priority, Puzzle, Table};
 
 #[derive(Debug)]
-struct Backpack {
-    combined: String,
-    first_compartment: String,
-    second_compartment: String,
+struct Backpack<'a> {
+    line: usize,
+    first_compartment: &'a str,
+    second_compartment: &'a str,
 }
 
-impl Backpack {
-    fn new(first: String, last: String) -> Self {
-        let mut combined = first.clone();
-        combined.push_str(last.as_str());
+impl<'a> Backpack<'a> {
+    fn new(line: usize, combined: &'a str) -> Self {
+        // items can be any unicode character, so split on a char boundary
+        let half = combined.chars().count() / 2;
+        let middle = combined
+            .char_indices()
+            .nth(half)
+            .map_or(combined.len(), |(i, _)| i);
+        let (first, last) = combined.split_at(middle);
         Self {
-            combined,
+            line,
             first_compartment: first,
             second_compartment: last,
         }
     }
+
+    fn items(&self) -> ItemSet {
+        ItemSet::from_items(self.first_compartment)
+            .union(&ItemSet::from_items(self.second_compartment))
+    }
+
+    fn shared_items(&self) -> ItemSet {
+        ItemSet::from_items(self.first_compartment)
+            .intersection(&ItemSet::from_items(self.second_compartment))
+    }
+
     fn find_common_item(&self) -> Option<char> {
-        let mut first_uniques: HashSet<char> = HashSet::from_iter(self.first_compartment.chars());
-        let second_uniques: HashSet<char> = HashSet::from_iter(self.second_compartment.chars());
-        for c in second_uniques.into_iter() {
-            let result = first_uniques.insert(c);
-            if !result {
-                return Some(c);
-            }
-        }
-        None
+        self.shared_items().first()
     }
 }
 
+// items carried by every backpack in the group
+fn badge_candidates(group: &[Backpack]) -> ItemSet {
+    group
+        .iter()
+        .map(Backpack::items)
+        .reduce(|a, b| a.intersection(&b))
+        .unwrap_or_default()
+}
+
+// the item carried by every backpack in each group of `size`
+fn find_badges(backpacks: &[Backpack], size: usize) -> Vec<Option<char>> {
+    backpacks
+        .chunks(size)
+        .map(|group| badge_candidates(group).first())
+        .collect()
+}
+
 fn main() {
     let input = read_to_string("./input.txt").unwrap();
 
-    let backpack_lines: Vec<&str> = input.split("\n").filter(|line| line.len() > 0).collect();
-    let backpacks: Vec<Backpack> = backpack_lines
-        .iter()
-        .map(|line| {
-            let line = line.to_owned();
-            let (left, right) = line.split_at(line.len() / 2);
-            Backpack::new(left.to_string(), right.to_string())
-        })
+    let backpacks: Vec<Backpack> = input
+        .split('\n')
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(index, line)| Backpack::new(index + 1, line))
         .collect();
 
+    // groups are three backpacks unless asked otherwise, `--group all` is the whole team
+    let args: Vec<String> = env::args().skip(1).collect();
+    let group_size = match option(&args, "--group") {
+        Some("all") => backpacks.len().max(1),
+        Some(size) => match size.parse() {
+            Ok(size) if size >= 1 => size,
+            _ => {
+                eprintln!(
+                    "--group expects a size of at least 1 or 'all', found '{}'",
+                    size
+                );
+                std::process::exit(1);
+            }
+        },
+        None => 3,
+    };
+
+    let priorities: Box<dyn Priority> = match option(&args, "--priorities") {
+        Some(path) => match Table::load(path) {
+            Ok(table) => Box::new(table),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        None => Box::new(Puzzle),
+    };
+
+    let problems = check::check(&backpacks, group_size);
+    if command(&args) == Some("check") {
+        for problem in &problems {
+            println!("{}", problem);
+        }
+        if problems.is_empty() {
+            println!("no problems found");
+        } else {
+            std::process::exit(1);
+        }
+        return;
+    }
+    if command(&args) == Some("reorganise") {
+        let group_trades = args.iter().any(|arg| arg == "--group-trades");
+        reorganise(&backpacks, group_size, group_trades);
+        return;
+    }
+    if !problems.is_empty() {
+        eprintln!(
+            "warning: {} integrity problem(s) found, run `check` to list them",
+            problems.len()
+        );
+    }
+
     // part 1
     let common_items: Vec<char> = backpacks
         .iter()
-        .map(|backpack| backpack.find_common_item().unwrap())
+        .filter_map(|backpack| backpack.find_common_item())
         .collect();
 
-    let score = calculate_score(common_items);
+    let score = calculate_score(common_items, priorities.as_ref());
 
     println!("Total score: {}", score);
 
     // part 2
-    // chunk backpacks into groups of three strings
-    let chunks = Vec::from_iter(backpacks.chunks(3));
-    let chunks: Vec<Vec<String>> = chunks
-        .into_iter()
-        .map(|chunk| chunk.into_iter().map(|backpack| backpack.combined.clone()))
-        .map(|a| Vec::from_iter(a))
-        .collect();
-
-    // find common char in each group of three strings
-    let mut common_items: Vec<char> = Vec::new();
-    for chunk in chunks {
-        // vec of 3 hashsets containing unique chars
-        let sets: Vec<HashSet<char>> = chunk
-            .iter()
-            .map(|c| HashSet::from_iter(c.chars()))
-            .collect();
-
-        let intersected: Vec<char> = sets
-            .into_iter()
-            .reduce(|a, b| HashSet::from_iter(a.intersection(&b).into_iter().map(|i| *i)))
-            .unwrap()
-            .into_iter()
-            .collect();
-
-        let result = intersected.get(0).unwrap().to_owned();
-
-        common_items.push(result);
+    // find common char in each group, larger groups may not share one
+    let badges = find_badges(&backpacks, group_size);
+    let missing = badges.iter().filter(|badge| badge.is_none()).count();
+    if missing > 0 {
+        println!("groups without a badge: {}", missing);
     }
+    let common_items: Vec<char> = badges.into_iter().flatten().collect();
 
     // call calculate_score on common chars from groups
-    let score = calculate_score(common_items);
+    let score = calculate_score(common_items, priorities.as_ref());
     println!("Grouped score: {}", score);
 }
 
-fn calculate_score(chars: Vec<char>) -> u32 {
-    let alphabet = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+fn reorganise(backpacks: &[Backpack], group_size: usize, group_trades: bool) {
+    let plan = reorganise::plan(backpacks, group_size, group_trades);
+    for swap in &plan.swaps {
+        println!("{}", swap);
+    }
+    println!("swaps: {}", plan.swaps.len());
+    for line in &plan.unresolved {
+        println!("line {}: can't be reorganised", line);
+    }
+
+    let dirty = reorganise::verify(&plan);
+    for (line, item) in &dirty {
+        println!("line {}: '{}' is still in both compartments", line, item);
+    }
+    if dirty.is_empty() {
+        println!("verified: no item is in both compartments of any backpack");
+    } else {
+        std::process::exit(1);
+    }
+}
+
+// flags that stand alone rather than taking a value
+const SWITCHES: [&str; 1] = ["--group-trades"];
+
+// first argument that isn't a `--flag` or its value
+fn command(args: &[String]) -> Option<&str> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--") {
+            if !SWITCHES.contains(&arg.as_str()) {
+                args.next();
+            }
+        } else {
+            return Some(arg.as_str());
+        }
+    }
+    None
+}
+
+fn option<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|value| value.as_str())
+}
+
+// items without a priority are reported and count for nothing
+fn calculate_score(chars: Vec<char>, priorities: &dyn Priority) -> u32 {
     let mut sum = 0;
     for c in chars.iter() {
-        let score: u32 = alphabet
-            .chars()
-            .position(|a| a == c.to_owned())
-            .unwrap()
-            .saturating_add(1) as u32;
-        sum += score;
+        match priorities.priority(*c) {
+            Some(score) => sum += score,
+            None => eprintln!("warning: no priority for item '{}'", c),
+        }
     }
     sum
 }
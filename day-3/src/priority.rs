@@ -0,0 +1,56 @@
+use std::{collections::HashMap, fs::read_to_string};
+
+// how much an item is worth when it turns up in the wrong place
+pub trait Priority {
+    fn priority(&self, item: char) -> Option<u32>;
+}
+
+// the puzzle's scheme: a-z are 1 to 26 and A-Z are 27 to 52
+pub struct Puzzle;
+
+impl Priority for Puzzle {
+    fn priority(&self, item: char) -> Option<u32> {
+        match item {
+            'a'..='z' => Some(item as u32 - 'a' as u32 + 1),
+            'A'..='Z' => Some(item as u32 - 'A' as u32 + 27),
+            _ => None,
+        }
+    }
+}
+
+// priorities loaded from a file with one `<item> <priority>` pair per line,
+// where the item is any single unicode character
+pub struct Table(HashMap<char, u32>);
+
+impl Table {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::parse(&contents).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut table = HashMap::new();
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut chars = line.chars();
+            let item = chars.next().unwrap();
+            let priority = chars
+                .as_str()
+                .trim()
+                .parse()
+                .map_err(|_| format!("line {}: expected '<item> <priority>'", index + 1))?;
+            if table.insert(item, priority).is_some() {
+                return Err(format!("line {}: item '{}' listed twice", index + 1, item));
+            }
+        }
+        Ok(Self(table))
+    }
+}
+
+impl Priority for Table {
+    fn priority(&self, item: char) -> Option<u32> {
+        self.0.get(&item).copied()
+    }
+}
@@ -0,0 +1,289 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::Backpack;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compartment {
+    First,
+    Second,
+}
+
+// one item in a particular compartment of a particular backpack
+#[derive(Clone, Copy, Debug)]
+pub struct Slot {
+    pub line: usize,
+    pub compartment: Compartment,
+    pub item: char,
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let compartment = match self.compartment {
+            Compartment::First => "first",
+            Compartment::Second => "second",
+        };
+        write!(f, "line {} {} '{}'", self.line, compartment, self.item)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Swap {
+    pub a: Slot,
+    pub b: Slot,
+}
+
+impl fmt::Display for Swap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "swap {} with {}", self.a, self.b)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Contents {
+    line: usize,
+    first: Vec<char>,
+    second: Vec<char>,
+}
+
+impl Contents {
+    fn compartment(&mut self, compartment: Compartment) -> &mut Vec<char> {
+        match compartment {
+            Compartment::First => &mut self.first,
+            Compartment::Second => &mut self.second,
+        }
+    }
+
+    fn take(&mut self, compartment: Compartment, item: char) {
+        let items = self.compartment(compartment);
+        let index = items.iter().position(|&i| i == item).unwrap();
+        items.swap_remove(index);
+    }
+
+    fn put(&mut self, compartment: Compartment, item: char) {
+        self.compartment(compartment).push(item);
+    }
+}
+
+#[derive(Debug)]
+pub struct Plan {
+    pub swaps: Vec<Swap>,
+    // backpacks that couldn't be cleaned up
+    pub unresolved: Vec<usize>,
+    pub result: Vec<(usize, String)>,
+}
+
+// each item type has to end up entirely in one compartment, and the first
+// compartment has to stay the same size. pick the types for the first
+// compartment (a subset sum over type counts) that pull in the fewest items
+// from the second, each of which is paired with one item pushed out.
+fn plan_backpack(contents: &Contents) -> Option<Vec<Swap>> {
+    let mut counts: BTreeMap<char, (usize, usize)> = BTreeMap::new();
+    for &item in &contents.first {
+        counts.entry(item).or_default().0 += 1;
+    }
+    for &item in &contents.second {
+        counts.entry(item).or_default().1 += 1;
+    }
+    let types: Vec<(char, usize, usize)> = counts
+        .into_iter()
+        .map(|(item, (first, second))| (item, first, second))
+        .collect();
+    let capacity = contents.first.len();
+
+    // best[i][j]: fewest items pulled in when the first i types fill j places
+    let mut best: Vec<Vec<Option<usize>>> = vec![vec![None; capacity + 1]; types.len() + 1];
+    best[0][0] = Some(0);
+    for (i, &(_, first, second)) in types.iter().enumerate() {
+        for j in 0..=capacity {
+            let skip = best[i][j];
+            let take = (j >= first + second)
+                .then(|| best[i][j - first - second].map(|cost| cost + second))
+                .flatten();
+            best[i + 1][j] = match (skip, take) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        }
+    }
+    best[types.len()][capacity]?;
+
+    let mut pull_in = vec![];
+    let mut push_out = vec![];
+    let mut j = capacity;
+    for i in (0..types.len()).rev() {
+        let (item, first, second) = types[i];
+        if best[i + 1][j] == best[i][j] {
+            push_out.extend(std::iter::repeat_n(item, first));
+        } else {
+            pull_in.extend(std::iter::repeat_n(item, second));
+            j -= first + second;
+        }
+    }
+
+    let swaps = push_out
+        .into_iter()
+        .zip(pull_in)
+        .map(|(out, into)| Swap {
+            a: Slot {
+                line: contents.line,
+                compartment: Compartment::First,
+                item: out,
+            },
+            b: Slot {
+                line: contents.line,
+                compartment: Compartment::Second,
+                item: into,
+            },
+        })
+        .collect();
+    Some(swaps)
+}
+
+fn apply(backpacks: &mut [Contents], swap: Swap) {
+    let a = backpacks
+        .iter()
+        .position(|b| b.line == swap.a.line)
+        .unwrap();
+    let b = backpacks
+        .iter()
+        .position(|b| b.line == swap.b.line)
+        .unwrap();
+    backpacks[a].take(swap.a.compartment, swap.a.item);
+    backpacks[b].take(swap.b.compartment, swap.b.item);
+    backpacks[a].put(swap.a.compartment, swap.b.item);
+    backpacks[b].put(swap.b.compartment, swap.a.item);
+}
+
+// distinct items in each compartment of a backpack
+fn slots(contents: &Contents) -> Vec<Slot> {
+    let mut slots = vec![];
+    for (compartment, items) in [
+        (Compartment::First, &contents.first),
+        (Compartment::Second, &contents.second),
+    ] {
+        let mut items = items.clone();
+        items.sort();
+        items.dedup();
+        slots.extend(items.into_iter().map(|item| Slot {
+            line: contents.line,
+            compartment,
+            item,
+        }));
+    }
+    slots
+}
+
+// a trade between two backpacks, then the swaps that sort out each of them
+type Trade = (Swap, Vec<Swap>, Vec<Swap>);
+
+// when a backpack can't be sorted out on its own, look for a single trade
+// with another backpack of its group that lets both of them be sorted out,
+// keeping the total number of swaps as low as possible
+fn trade_within_group(group: &[Contents], stuck: usize) -> Option<Trade> {
+    let mut best: Option<(usize, Trade)> = None;
+    for (other, partner) in group.iter().enumerate() {
+        if other == stuck {
+            continue;
+        }
+        for a in slots(&group[stuck]) {
+            for b in slots(partner) {
+                let trade = Swap { a, b };
+                let mut traded = [group[stuck].clone(), partner.clone()];
+                apply(&mut traded, trade);
+                let (Some(first), Some(second)) =
+                    (plan_backpack(&traded[0]), plan_backpack(&traded[1]))
+                else {
+                    continue;
+                };
+                let cost = 1 + first.len() + second.len();
+                if best.as_ref().is_none_or(|(best, _)| cost < *best) {
+                    best = Some((cost, (trade, first, second)));
+                }
+            }
+        }
+    }
+    best.map(|(_, plan)| plan)
+}
+
+pub fn plan(backpacks: &[Backpack], group_size: usize, group_trades: bool) -> Plan {
+    let mut contents: Vec<Contents> = backpacks
+        .iter()
+        .map(|backpack| Contents {
+            line: backpack.line,
+            first: backpack.first_compartment.chars().collect(),
+            second: backpack.second_compartment.chars().collect(),
+        })
+        .collect();
+
+    let mut swaps = vec![];
+    let mut unresolved = vec![];
+    for group in contents.chunks_mut(group_size) {
+        let mut stuck = vec![];
+        let mut group_swaps = vec![];
+        for (index, backpack) in group.iter().enumerate() {
+            match plan_backpack(backpack) {
+                Some(plan) => group_swaps.push((backpack.line, plan)),
+                None => stuck.push(index),
+            }
+        }
+
+        for index in stuck {
+            // an earlier trade may already have sorted this one out
+            if group_swaps
+                .iter()
+                .any(|(line, _)| *line == group[index].line)
+            {
+                continue;
+            }
+            let traded = group_trades
+                .then(|| trade_within_group(group, index))
+                .flatten();
+            let Some((trade, first, second)) = traded else {
+                unresolved.push(group[index].line);
+                continue;
+            };
+            // the partner's earlier swaps are replaced by its new plan
+            group_swaps.retain(|(line, _)| *line != trade.b.line);
+            apply(group, trade);
+            swaps.push(trade);
+            group_swaps.push((trade.a.line, first));
+            group_swaps.push((trade.b.line, second));
+        }
+
+        group_swaps.sort_by_key(|(line, _)| *line);
+        for (_, plan) in group_swaps {
+            for swap in plan {
+                apply(group, swap);
+                swaps.push(swap);
+            }
+        }
+    }
+
+    let result = contents
+        .into_iter()
+        .map(|backpack| {
+            let mut items: String = backpack.first.into_iter().collect();
+            items.extend(backpack.second);
+            (backpack.line, items)
+        })
+        .collect();
+
+    Plan {
+        swaps,
+        unresolved,
+        result,
+    }
+}
+
+// re-reads the reorganised backpacks and returns any that still have an
+// item in both compartments
+pub fn verify(plan: &Plan) -> Vec<(usize, char)> {
+    plan.result
+        .iter()
+        .filter_map(|(line, items)| {
+            Backpack::new(*line, items)
+                .find_common_item()
+                .map(|item| (*line, item))
+        })
+        .collect()
+}
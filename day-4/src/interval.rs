@@ -0,0 +1,130 @@
+use std::{fmt, ops::RangeInclusive};
+
+// an inclusive range of section ids
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Section {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Section {
+    pub fn range(self) -> RangeInclusive<u32> {
+        self.start..=self.end
+    }
+
+    pub fn len(self) -> u32 {
+        self.end - self.start + 1
+    }
+
+    pub fn contains(self, other: Section) -> bool {
+        let range = self.range();
+        range.contains(&other.start) && range.contains(&other.end)
+    }
+
+    pub fn overlaps(self, other: Section) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(self, other: Section) -> Option<Section> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        (start <= end).then_some(Section { start, end })
+    }
+
+    // one section when they overlap or touch, otherwise both of them in order
+    pub fn union(self, other: Section) -> SectionSet {
+        SectionSet::merge([self, other])
+    }
+
+    // the parts of this section not covered by `other`
+    pub fn difference(self, other: Section) -> SectionSet {
+        let Some(common) = self.intersection(other) else {
+            return SectionSet::merge([self]);
+        };
+        let mut parts = vec![];
+        if self.start < common.start {
+            parts.push(Section {
+                start: self.start,
+                end: common.start - 1,
+            });
+        }
+        if common.end < self.end {
+            parts.push(Section {
+                start: common.end + 1,
+                end: self.end,
+            });
+        }
+        SectionSet(parts)
+    }
+}
+
+impl TryFrom<&str> for Section {
+    type Error = &'static str;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let Some((a, b)) = value.split_once('-') else {
+            return Err("expected a range like '2-4'");
+        };
+        let start: u32 = a.parse().map_err(|_| "range start is not a number")?;
+        let end: u32 = b.parse().map_err(|_| "range end is not a number")?;
+        if start > end {
+            return Err("range starts after it ends");
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+// disjoint sections in ascending order, with no two touching
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SectionSet(Vec<Section>);
+
+impl SectionSet {
+    pub fn merge(sections: impl IntoIterator<Item = Section>) -> Self {
+        let mut sections: Vec<Section> = sections.into_iter().collect();
+        sections.sort();
+        let mut merged: Vec<Section> = vec![];
+        for section in sections {
+            match merged.last_mut() {
+                Some(last) if section.start <= last.end.saturating_add(1) => {
+                    last.end = last.end.max(section.end);
+                }
+                _ => merged.push(section),
+            }
+        }
+        Self(merged)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> u32 {
+        self.0.iter().map(|section| section.len()).sum()
+    }
+
+    // the uncovered stretches between the first and last covered section
+    pub fn gaps(&self) -> SectionSet {
+        Self(
+            self.0
+                .windows(2)
+                .map(|pair| Section {
+                    start: pair[0].end + 1,
+                    end: pair[1].start - 1,
+                })
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for SectionSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = self.0.iter().map(|section| section.to_string()).collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
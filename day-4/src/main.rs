@@ -1,71 +1,95 @@
-use std::{fs::read_to_string, ops::RangeInclusive};
+use std::{env, fs::read_to_string};
 
-#[derive(Copy, Clone, Debug)]
-struct Section {
-    start: u32,
-    end: u32,
-}
-
-impl Section {
-    fn range(self) -> RangeInclusive<u32> {
-        self.start..=self.end
-    }
-}
-
-impl TryFrom<&str> for Section {
-    type Error = &'static str;
+mod interval;
+mod matrix;
+mod sweep;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let parts: Vec<&str> = value.split("-").collect();
-        let a = parts.get(0).unwrap().to_owned();
-        let b = parts.get(1).unwrap().to_owned();
-        let start: u32 = a.parse().unwrap();
-        let end: u32 = b.parse().unwrap();
-        Ok(Self { start, end })
-    }
-}
+use interval::{Section, SectionSet};
+use sweep::Assignment;
 
-#[derive(Clone, Copy)]
+// the elves named on one line of the input, any number of them
 struct Group {
-    a: Section,
-    b: Section,
+    // line of the input the group was read from
+    line: usize,
+    sections: Vec<Section>,
 }
 
 impl Group {
-    fn contains(self) -> bool {
-        let a_range = self.a.range();
-        let b_range = self.b.range();
-        let a_contains_b = a_range.contains(&self.b.start) && a_range.contains(&self.b.end);
-        let b_contains_a = b_range.contains(&self.a.start) && b_range.contains(&self.a.end);
-        a_contains_b || b_contains_a
+    fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let n = self.sections.len();
+        (0..n).flat_map(move |i| (i + 1..n).map(move |j| (i, j)))
+    }
+
+    fn contains(&self) -> bool {
+        self.pairs().any(|(i, j)| {
+            let (a, b) = (self.sections[i], self.sections[j]);
+            a.contains(b) || b.contains(a)
+        })
     }
 
-    fn overlaps(self) -> bool {
-        self.a.start <= self.b.end && self.b.start <= self.a.end
+    fn overlaps(&self) -> bool {
+        self.pairs()
+            .any(|(i, j)| self.sections[i].overlaps(self.sections[j]))
     }
 }
 
 fn main() {
     let input = read_to_string("./input.txt").unwrap();
 
-    // 1. split input into rows
-    let rows: Vec<&str> = input.split("\n").filter(|i| i.len() > 0).collect();
+    // 1. split input into rows, keeping their line numbers
+    let rows: Vec<(usize, &str)> = input
+        .split('\n')
+        .enumerate()
+        .filter(|(_, i)| !i.is_empty())
+        .collect();
 
     // 2. convert rows into groups of sections
     let groups: Vec<Group> = rows
         .into_iter()
-        .map(|row| {
-            let section_inputs: Vec<&str> = row.split(",").collect();
-            let sections: Vec<Section> = section_inputs
-                .into_iter()
-                .map(|i| Section::try_from(i).unwrap())
-                .collect();
-            let a = sections.get(0).unwrap();
-            let b = sections.get(1).unwrap();
-            Group { a: *a, b: *b }
+        .map(|(index, row)| Group {
+            line: index + 1,
+            sections: row
+                .split(',')
+                .map(|i| match Section::try_from(i) {
+                    Ok(section) => section,
+                    Err(e) => {
+                        eprintln!("line {}: {}: '{}'", index + 1, e, i);
+                        std::process::exit(1);
+                    }
+                })
+                .collect(),
         })
         .collect();
 
+    match env::args().nth(1).as_deref() {
+        Some("coverage") => {
+            coverage(&groups);
+            return;
+        }
+        Some("sweep") => {
+            sweep(&groups);
+            return;
+        }
+        Some("matrix") => {
+            println!(
+                "row elf vs column elf: > contains, < contained by, = same, x overlaps, . disjoint"
+            );
+            for group in &groups {
+                print!("{}", matrix::render_text(group.line, &group.sections));
+            }
+            return;
+        }
+        Some("dot") => {
+            let sections: Vec<(usize, &[Section])> = groups
+                .iter()
+                .map(|g| (g.line, g.sections.as_slice()))
+                .collect();
+            print!("{}", matrix::render_dot(&sections));
+            return;
+        }
+        _ => {}
+    }
+
     // 3. count how many contain themselves
     let mut contains_count = 0;
     for group in &groups {
@@ -80,7 +104,7 @@ fn main() {
     // part 2
     // 5. count how many overlap
     let mut overlaps_count = 0;
-    for group in groups {
+    for group in &groups {
         if group.overlaps() {
             overlaps_count += 1;
         }
@@ -89,3 +113,77 @@ fn main() {
     // 6. print the total overlapping groups to solve part 2
     println!("Total overlapping groups: {}", overlaps_count);
 }
+
+fn coverage(groups: &[Group]) {
+    let covered = SectionSet::merge(groups.iter().flat_map(|group| group.sections.clone()));
+    println!(
+        "sections covered by the team: {} ({})",
+        covered.len(),
+        covered
+    );
+
+    let gaps = covered.gaps();
+    if gaps.is_empty() {
+        println!("unassigned sections inside the camp: none");
+    } else {
+        println!(
+            "unassigned sections inside the camp: {} ({})",
+            gaps.len(),
+            gaps
+        );
+    }
+
+    println!("overlap per pair:");
+    for group in groups {
+        for (i, j) in group.pairs() {
+            let (a, b) = (group.sections[i], group.sections[j]);
+            println!(
+                "  line {} elves {}/{}: {},{} share {} of {} sections, {} only first, {} only second",
+                group.line,
+                i + 1,
+                j + 1,
+                a,
+                b,
+                a.intersection(b).map_or(0, Section::len),
+                a.union(b).len(),
+                a.difference(b).len(),
+                b.difference(a).len()
+            );
+        }
+    }
+}
+
+fn sweep(groups: &[Group]) {
+    let assignments: Vec<Assignment> = groups
+        .iter()
+        .flat_map(|group| {
+            group
+                .sections
+                .iter()
+                .copied()
+                .enumerate()
+                .map(move |(elf, section)| Assignment {
+                    line: group.line,
+                    elf: elf + 1,
+                    section,
+                })
+        })
+        .collect();
+
+    let coverage = sweep::analyse(&assignments);
+    println!("most elves on one section: {}", coverage.max_depth);
+    println!("  at sections: {}", coverage.deepest);
+
+    println!("depth histogram (elves: sections):");
+    for (depth, count) in &coverage.histogram {
+        println!("  {:>4}: {}", depth, count);
+    }
+
+    println!("redundant assignments: {}", coverage.redundant.len());
+    for assignment in &coverage.redundant {
+        println!(
+            "  line {} elf {}: {}",
+            assignment.line, assignment.elf, assignment.section
+        );
+    }
+}
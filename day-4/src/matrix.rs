@@ -0,0 +1,132 @@
+use std::fmt::Write;
+
+use crate::interval::Section;
+
+// how the row elf's assignment relates to the column elf's
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    Same,
+    Contains,
+    ContainedBy,
+    Overlaps,
+    Disjoint,
+}
+
+impl Relation {
+    pub fn between(a: Section, b: Section) -> Self {
+        match (a.contains(b), b.contains(a)) {
+            (true, true) => Relation::Same,
+            (true, false) => Relation::Contains,
+            (false, true) => Relation::ContainedBy,
+            _ if a.overlaps(b) => Relation::Overlaps,
+            _ => Relation::Disjoint,
+        }
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Relation::Same => '=',
+            Relation::Contains => '>',
+            Relation::ContainedBy => '<',
+            Relation::Overlaps => 'x',
+            Relation::Disjoint => '.',
+        }
+    }
+}
+
+pub fn relations(sections: &[Section]) -> Vec<Vec<Relation>> {
+    sections
+        .iter()
+        .map(|&a| sections.iter().map(|&b| Relation::between(a, b)).collect())
+        .collect()
+}
+
+// edges (i, j) where elf i's work subsumes elf j's. identical assignments
+// point from the earlier elf to the later one so the graph stays acyclic,
+// and edges implied by a longer path are dropped.
+pub fn containment_dag(sections: &[Section]) -> Vec<(usize, usize)> {
+    let n = sections.len();
+    let subsumes = |i: usize, j: usize| match Relation::between(sections[i], sections[j]) {
+        Relation::Contains => true,
+        Relation::Same => i < j,
+        _ => false,
+    };
+    let mut edges = vec![];
+    for i in 0..n {
+        for j in 0..n {
+            if i == j || !subsumes(i, j) {
+                continue;
+            }
+            let implied = (0..n).any(|k| k != i && k != j && subsumes(i, k) && subsumes(k, j));
+            if !implied {
+                edges.push((i, j));
+            }
+        }
+    }
+    edges
+}
+
+pub fn render_text(line: usize, sections: &[Section]) -> String {
+    let labels: Vec<String> = sections.iter().map(|s| s.to_string()).collect();
+    let width = labels.iter().map(|l| l.len()).max().unwrap_or(0);
+    let mut out = String::new();
+
+    writeln!(out, "line {}:", line).unwrap();
+    write!(out, "  {:width$}  ", "", width = width).unwrap();
+    for i in 0..sections.len() {
+        write!(out, " {}", i + 1).unwrap();
+    }
+    writeln!(out).unwrap();
+    for (i, row) in relations(sections).iter().enumerate() {
+        write!(out, "  {:>width$} {}", labels[i], i + 1, width = width).unwrap();
+        for (j, relation) in row.iter().enumerate() {
+            let symbol = if i == j { '-' } else { relation.symbol() };
+            write!(out, " {}", symbol).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+
+    let edges = containment_dag(sections);
+    if edges.is_empty() {
+        writeln!(out, "  no elf's work is subsumed by another's").unwrap();
+    }
+    for (i, j) in edges {
+        writeln!(
+            out,
+            "  elf {} ({}) subsumes elf {} ({})",
+            i + 1,
+            labels[i],
+            j + 1,
+            labels[j]
+        )
+        .unwrap();
+    }
+    out
+}
+
+// one cluster per input line, with an edge from each elf to the elves whose
+// work it subsumes. groups come with the line they were read from.
+pub fn render_dot(groups: &[(usize, &[Section])]) -> String {
+    let mut out = String::from("digraph containment {\n    node [shape=box];\n");
+    for &(line, sections) in groups {
+        writeln!(out, "    subgraph cluster_{} {{", line).unwrap();
+        writeln!(out, "        label=\"line {}\";", line).unwrap();
+        for (elf, section) in sections.iter().enumerate() {
+            writeln!(
+                out,
+                "        l{}e{} [label=\"elf {}\\n{}\"];",
+                line,
+                elf + 1,
+                elf + 1,
+                section
+            )
+            .unwrap();
+        }
+        for (i, j) in containment_dag(sections) {
+            writeln!(out, "        l{}e{} -> l{}e{};", line, i + 1, line, j + 1).unwrap();
+        }
+        writeln!(out, "    }}").unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
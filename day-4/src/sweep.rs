@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+
+use crate::interval::{Section, SectionSet};
+
+// one elf's assignment, `elf` being its position on the input line
+#[derive(Clone, Copy, Debug)]
+pub struct Assignment {
+    pub line: usize,
+    pub elf: usize,
+    pub section: Section,
+}
+
+#[derive(Debug)]
+pub struct Coverage {
+    pub max_depth: u32,
+    pub deepest: SectionSet,
+    // depth -> number of section ids at that depth, between the lowest and highest assigned
+    pub histogram: BTreeMap<u32, u64>,
+    // assignments where every section is also given to someone else
+    pub redundant: Vec<Assignment>,
+}
+
+// a stretch of section ids [start, end) with the same number of elves on it
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    start: u64,
+    end: u64,
+    depth: u32,
+}
+
+// sorts the start and end of every assignment and sweeps across them once,
+// so the whole analysis is O(n log n) in the number of assignments
+pub fn analyse(assignments: &[Assignment]) -> Coverage {
+    let mut events: Vec<(u64, i64)> = assignments
+        .iter()
+        .flat_map(|a| [(a.section.start as u64, 1), (a.section.end as u64 + 1, -1)])
+        .collect();
+    events.sort();
+
+    let mut segments: Vec<Segment> = vec![];
+    let mut depth: i64 = 0;
+    let mut i = 0;
+    while i < events.len() {
+        let position = events[i].0;
+        while i < events.len() && events[i].0 == position {
+            depth += events[i].1;
+            i += 1;
+        }
+        if let Some(&(next, _)) = events.get(i) {
+            segments.push(Segment {
+                start: position,
+                end: next,
+                depth: depth as u32,
+            });
+        }
+    }
+
+    let mut histogram = BTreeMap::new();
+    for segment in &segments {
+        *histogram.entry(segment.depth).or_insert(0) += segment.end - segment.start;
+    }
+
+    let max_depth = segments.iter().map(|s| s.depth).max().unwrap_or(0);
+    let deepest = SectionSet::merge(
+        segments
+            .iter()
+            .filter(|s| s.depth == max_depth && max_depth > 0)
+            .map(|s| Section {
+                start: s.start as u32,
+                end: (s.end - 1) as u32,
+            }),
+    );
+
+    let table = MinTable::new(segments.iter().map(|s| s.depth).collect());
+    let redundant = assignments
+        .iter()
+        .filter(|a| {
+            let start = a.section.start as u64;
+            let end = a.section.end as u64 + 1;
+            let first = segments.partition_point(|s| s.end <= start);
+            let last = segments.partition_point(|s| s.start < end);
+            // the elf itself accounts for one on every section it covers
+            table.min(first, last) >= 2
+        })
+        .copied()
+        .collect();
+
+    Coverage {
+        max_depth,
+        deepest,
+        histogram,
+        redundant,
+    }
+}
+
+// sparse table answering "smallest value in [from, to)" in constant time
+struct MinTable {
+    levels: Vec<Vec<u32>>,
+}
+
+impl MinTable {
+    fn new(values: Vec<u32>) -> Self {
+        let n = values.len();
+        let mut levels = vec![values];
+        let mut width = 1;
+        while width * 2 <= n {
+            let previous = levels.last().unwrap();
+            let next = (0..=n - width * 2)
+                .map(|i| previous[i].min(previous[i + width]))
+                .collect();
+            levels.push(next);
+            width *= 2;
+        }
+        Self { levels }
+    }
+
+    fn min(&self, from: usize, to: usize) -> u32 {
+        if from >= to {
+            return 0;
+        }
+        let level = (usize::BITS - 1 - (to - from).leading_zeros()) as usize;
+        let width = 1 << level;
+        self.levels[level][from].min(self.levels[level][to - width])
+    }
+}
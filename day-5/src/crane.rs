@@ -0,0 +1,106 @@
+use std::fmt;
+
+use crate::ship::Ship;
+
+// a `move N from A to B` line of the rearrangement procedure
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub amount: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl TryFrom<&str> for Instruction {
+    type Error = &'static str;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let parts: Vec<&str> = value.split_whitespace().collect();
+        let ["move", amount, "from", from, "to", to] = parts[..] else {
+            return Err("expected 'move N from A to B'");
+        };
+        let parse = |part: &str| part.parse().map_err(|_| "expected a number");
+        Ok(Self {
+            amount: parse(amount)?,
+            from: parse(from)?,
+            to: parse(to)?,
+        })
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.amount, self.from, self.to)
+    }
+}
+
+// a crane model: how a number of crates get from one stack to another
+pub trait CrateMover {
+    fn name(&self) -> String;
+
+    // the largest number of crates lifted at once, keeping their order
+    fn lift_size(&self, amount: usize) -> usize;
+
+    fn apply(&self, ship: &mut Ship, instruction: Instruction) -> Result<(), &'static str> {
+        let Instruction { amount, from, to } = instruction;
+        if from == to {
+            // putting crates back where they came from leaves the stack as it was
+            let stack = ship.stack(from).ok_or("unknown stack")?;
+            if amount > stack.len() {
+                return Err("not enough crates on the stack");
+            }
+            return Ok(());
+        }
+        let (from_stack, to_stack) = ship.stacks_mut(from, to).ok_or("unknown stack")?;
+        if amount > from_stack.len() {
+            return Err("not enough crates on the stack");
+        }
+        let lift = self.lift_size(amount).max(1);
+        let mut remaining = amount;
+        while remaining > 0 {
+            let size = lift.min(remaining);
+            let start = from_stack.len() - size;
+            to_stack.extend(from_stack.drain(start..));
+            remaining -= size;
+        }
+        Ok(())
+    }
+}
+
+// moves one crate at a time
+pub struct CrateMover9000;
+
+impl CrateMover for CrateMover9000 {
+    fn name(&self) -> String {
+        String::from("CrateMover 9000")
+    }
+
+    fn lift_size(&self, _amount: usize) -> usize {
+        1
+    }
+}
+
+// moves all the crates at once
+pub struct CrateMover9001;
+
+impl CrateMover for CrateMover9001 {
+    fn name(&self) -> String {
+        String::from("CrateMover 9001")
+    }
+
+    fn lift_size(&self, amount: usize) -> usize {
+        amount
+    }
+}
+
+// can only lift so many crates at once
+pub struct Limited(pub usize);
+
+impl CrateMover for Limited {
+    fn name(&self) -> String {
+        format!("crane lifting up to {}", self.0)
+    }
+
+    fn lift_size(&self, amount: usize) -> usize {
+        self.0.min(amount)
+    }
+}
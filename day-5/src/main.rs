@@ -1,89 +1,225 @@
-use std::{collections::BTreeMap, fs::read_to_string};
+use std::{
+    env,
+    fs::read_to_string,
+    io::{self, BufRead},
+};
+
+mod crane;
+mod plan;
+mod replay;
+mod ship;
+
+use crane::{CrateMover, CrateMover9000, CrateMover9001, Instruction, Limited};
+use plan::{Outcome, Target};
+use replay::Replay;
+use ship::Ship;
 
 fn main() {
     let input = read_to_string("./input.txt").unwrap();
-    let lines: Vec<&str> = input.lines().collect();
-    let split: Vec<Vec<&str>> = lines
-        .split(|line| line.to_owned() == "")
-        .map(|line| line.to_owned())
-        .collect();
-    let initial_state = split.get(0).unwrap().to_owned();
-    let instructions = split.get(1).unwrap().to_owned();
-
-    let mut state: BTreeMap<usize, Vec<char>> = BTreeMap::new();
-
-    // transform crates from diagram into hashmap
-    for i in initial_state {
-        for (idx, c) in i.char_indices() {
-            let scaled_idx = (idx / 4).saturating_add(1);
-            if c.is_alphabetic() {
-                if let Some(vec) = state.get_mut(&scaled_idx) {
-                    vec.push(c);
-                } else {
-                    let mut new_vec = Vec::new();
-                    new_vec.push(c);
-                    state.insert(scaled_idx, new_vec);
-                }
-            }
+    let (drawing, instructions) = input.split_once("\n\n").unwrap();
+
+    let state = match Ship::parse(drawing) {
+        Ok(ship) => ship,
+        Err(e) => fail(e),
+    };
+
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // the parser and renderer have to agree with each other and with the drawing
+    if command(&args) == Some("render") {
+        println!("{}", state);
+        let rendered = state.to_string();
+        if rendered != drawing || Ship::parse(&rendered).as_ref() != Ok(&state) {
+            fail("rendered drawing does not match the input");
         }
+        return;
     }
 
-    // reverse order of crates
-    for (_, v) in state.iter_mut() {
-        v.reverse();
-    }
+    // instructions start after the drawing and the blank line below it
+    let first_line = drawing.lines().count() + 2;
+    let instructions: Vec<(usize, Instruction)> = instructions
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(index, line)| {
+            let number = first_line + index;
+            match Instruction::try_from(line) {
+                Ok(instruction) => (number, instruction),
+                Err(e) => fail(format!("line {}: {}: '{}'", number, e, line)),
+            }
+        })
+        .collect();
 
-    // make copy of state for part 2
-    let mut state_2 = state.clone();
-
-    // process instructions
-    for instruction in instructions {
-        let parts: Vec<&str> = instruction.split_whitespace().collect();
-        let amount: usize = parts.get(1).unwrap().to_owned().parse().unwrap();
-        let from: usize = parts.get(3).unwrap().to_owned().parse().unwrap();
-        let to: usize = parts.get(5).unwrap().to_owned().parse().unwrap();
-
-        // part 1
-        let mut from_stack = Vec::from_iter(state.get(&from).unwrap().iter().map(|i| i.to_owned()));
-        let mut to_stack = Vec::from_iter(state.get(&to).unwrap().iter().map(|i| i.to_owned()));
-
-        for _ in 0..amount {
-            // pop from end of from
-            let c = from_stack.pop().unwrap().to_owned();
-            // push to end of to
-            to_stack.push(c);
-        }
+    if command(&args) == Some("plan") {
+        let Some(target) = operands(&args).get(1).copied() else {
+            fail("usage: plan <tops> [--crane 9000|9001] [--max-depth N] [--budget N]");
+        };
+        let crane = crane(&args).unwrap_or_else(|| Box::new(CrateMover9000));
+        plan(&state, crane.as_ref(), target, &args);
+        return;
+    }
 
-        // replace vecs in state
-        state.insert(from, from_stack);
-        state.insert(to, to_stack);
+    if command(&args) == Some("replay") {
+        // `--crane` wins over `--lift` when both are given
+        let crane = crane(&args)
+            .or_else(|| lift(&args).map(|crane| Box::new(crane) as Box<dyn CrateMover>))
+            .unwrap_or_else(|| Box::new(CrateMover9000));
+        replay(Replay::new(state, crane.as_ref(), &instructions));
+        return;
+    }
 
-        // part 2
-        let mut from_stack =
-            Vec::from_iter(state_2.get(&from).unwrap().iter().map(|i| i.to_owned()));
-        let mut to_stack = Vec::from_iter(state_2.get(&to).unwrap().iter().map(|i| i.to_owned()));
+    // log top of each stack
+    println!(
+        "{}",
+        rearrange(&CrateMover9000, &state, &instructions).tops()
+    );
+    println!(
+        "{}",
+        rearrange(&CrateMover9001, &state, &instructions).tops()
+    );
 
-        // pop amount from end of from
-        let range = (from_stack.len() - amount)..(from_stack.len());
-        let c: Vec<char> = from_stack.drain(range).collect();
-        to_stack.extend(c);
+    // `--lift k` also tries a crane that can only lift k crates at a time
+    if let Some(crane) = lift(&args) {
+        let ship = rearrange(&crane, &state, &instructions);
+        println!("{}: {}", crane.name(), ship.tops());
+    }
+}
 
-        // replace vecs in state
-        state_2.insert(from, from_stack);
-        state_2.insert(to, to_stack);
+fn rearrange(crane: &dyn CrateMover, ship: &Ship, instructions: &[(usize, Instruction)]) -> Ship {
+    let mut replay = Replay::new(ship.clone(), crane, instructions);
+    if let Err(e) = replay.seek(instructions.len()) {
+        fail(format!("{}: {}", crane.name(), e));
     }
+    replay.ship().clone()
+}
 
-    // log top of each stack
-    let result: String = state
-        .iter()
-        .map(|(_, stack)| stack.last().unwrap())
-        .collect();
+// searches for a shortest procedure leaving `target` on top of the stacks and
+// prints it as a complete input, drawing included, so it can be run again
+fn plan(state: &Ship, crane: &dyn CrateMover, target: &str, args: &[String]) {
+    let target = match Target::parse(target, state) {
+        Ok(target) => target,
+        Err(e) => fail(e),
+    };
+    let max_depth = option(args, "--max-depth").map_or(12, |n| n.parse().unwrap());
+    let budget = option(args, "--budget").map_or(20_000_000, |n| n.parse().unwrap());
+
+    let moves = match plan::plan(state, crane, &target, max_depth, budget) {
+        Outcome::Found(moves) => moves,
+        Outcome::Exhausted => fail(format!("no plan within {} moves", max_depth)),
+        Outcome::GaveUp => fail(format!("gave up after {} states", budget)),
+    };
 
-    let result_2: String = state_2
+    // run the plan through the solver to make sure it really gets there
+    let first_line = state.to_string().lines().count() + 2;
+    let numbered: Vec<(usize, Instruction)> = moves
         .iter()
-        .map(|(_, stack)| stack.last().unwrap())
+        .enumerate()
+        .map(|(index, &instruction)| (first_line + index, instruction))
         .collect();
+    if !target.reached(&rearrange(crane, state, &numbered)) {
+        fail("plan does not reach the target");
+    }
+
+    println!("{}\n", state);
+    for instruction in &moves {
+        println!("{}", instruction);
+    }
+    eprintln!("{}: {} moves", crane.name(), moves.len());
+}
+
+// steps through the procedure with commands read from stdin:
+// `n [k]` forwards, `b [k]` backwards, `g <index>` to jump, `q` to stop
+fn replay(mut replay: Replay) {
+    show(&replay);
+    for line in io::stdin().lock().lines() {
+        let line = line.unwrap();
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let count = |default: usize| {
+            parts
+                .get(1)
+                .and_then(|count| count.parse().ok())
+                .unwrap_or(default)
+        };
+        let target = match parts.first().copied() {
+            Some("n") => replay.position() + count(1),
+            Some("b") => replay.position().saturating_sub(count(1)),
+            Some("g") => count(replay.position()),
+            Some("q") => return,
+            _ => {
+                println!("commands: n [k], b [k], g <index>, q");
+                continue;
+            }
+        };
+        let result = replay.seek(target);
+        show(&replay);
+        if let Err(e) = result {
+            println!("stopped at {}", e);
+        }
+    }
+}
+
+fn show(replay: &Replay) {
+    match replay.last() {
+        Some((line, instruction)) => println!(
+            "after {} of {} (line {}: {}):",
+            replay.position(),
+            replay.len(),
+            line,
+            instruction
+        ),
+        None => println!("start, {} instructions:", replay.len()),
+    }
+    println!("{}\n", replay.ship());
+}
+
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+// the crane named by `--crane`, if there is one
+fn crane(args: &[String]) -> Option<Box<dyn CrateMover>> {
+    match option(args, "--crane")? {
+        "9000" => Some(Box::new(CrateMover9000)),
+        "9001" => Some(Box::new(CrateMover9001)),
+        other => fail(format!("unknown crane '{}', expected 9000 or 9001", other)),
+    }
+}
+
+// a crane lifting at most `--lift` crates at a time
+fn lift(args: &[String]) -> Option<Limited> {
+    let k = option(args, "--lift")?;
+    match k.parse() {
+        Ok(k) if k >= 1 => Some(Limited(k)),
+        _ => fail(format!(
+            "--lift expects a number of crates, at least 1, found '{}'",
+            k
+        )),
+    }
+}
+
+// arguments that aren't a `--flag` or its value
+fn operands(args: &[String]) -> Vec<&str> {
+    let mut operands = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--") {
+            args.next();
+        } else {
+            operands.push(arg.as_str());
+        }
+    }
+    operands
+}
+
+// first argument that isn't a `--flag` or its value
+fn command(args: &[String]) -> Option<&str> {
+    operands(args).first().copied()
+}
 
-    println!("{}", result);
-    println!("{}", result_2);
+fn option<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|value| value.as_str())
 }
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use crate::{
+    crane::{CrateMover, Instruction},
+    ship::Ship,
+};
+
+// the crate wanted on top of each stack: a letter, `_` for an empty stack
+// or `?` when any top will do
+#[derive(Debug)]
+pub struct Target(Vec<char>);
+
+impl Target {
+    pub fn parse(target: &str, ship: &Ship) -> Result<Self, String> {
+        let wanted: Vec<char> = target.chars().collect();
+        if wanted.len() != ship.stack_count() {
+            return Err(format!(
+                "target has {} tops but the ship has {} stacks",
+                wanted.len(),
+                ship.stack_count()
+            ));
+        }
+        if let Some(c) = wanted
+            .iter()
+            .find(|c| !c.is_ascii_alphabetic() && **c != '_' && **c != '?')
+        {
+            return Err(format!("'{}' can't be the top of a stack", c));
+        }
+        Ok(Self(wanted))
+    }
+
+    fn satisfied(&self, label: usize, stack: &[char]) -> bool {
+        match self.0[label - 1] {
+            '?' => true,
+            '_' => stack.is_empty(),
+            c => stack.last() == Some(&c),
+        }
+    }
+
+    pub fn reached(&self, ship: &Ship) -> bool {
+        (1..=ship.stack_count()).all(|label| self.satisfied(label, ship.stack(label).unwrap()))
+    }
+
+    // a lower bound on the moves left. every move touches two stacks, and a
+    // stack that doesn't hold the crate it needs has to be moved onto.
+    fn estimate(&self, ship: &Ship) -> usize {
+        let mut wrong: usize = 0;
+        let mut needs_crate = 0;
+        for label in 1..=ship.stack_count() {
+            let stack = ship.stack(label).unwrap();
+            if self.satisfied(label, stack) {
+                continue;
+            }
+            wrong += 1;
+            let c = self.0[label - 1];
+            if c != '_' && !stack.contains(&c) {
+                needs_crate += 1;
+            }
+        }
+        wrong.div_ceil(2).max(needs_crate)
+    }
+}
+
+pub enum Outcome {
+    Found(Vec<Instruction>),
+    // nothing within the depth limit
+    Exhausted,
+    // generated more states than the budget allows before the search finished
+    GaveUp,
+}
+
+struct Search<'a> {
+    crane: &'a dyn CrateMover,
+    target: &'a Target,
+    path: Vec<Instruction>,
+    // shallowest depth each state has been reached at in this iteration
+    seen: HashMap<Ship, usize>,
+    nodes: usize,
+    budget: usize,
+}
+
+impl Search<'_> {
+    // depth first search below `limit` moves, true once the target is reached
+    fn run(&mut self, ship: &mut Ship, limit: usize) -> Option<bool> {
+        if self.target.reached(ship) {
+            return Some(true);
+        }
+        let depth = self.path.len();
+        if depth + self.target.estimate(ship) > limit {
+            return Some(false);
+        }
+        if self.seen.get(ship).is_some_and(|&seen| seen <= depth) {
+            return Some(false);
+        }
+        self.seen.insert(ship.clone(), depth);
+
+        let count = ship.stack_count();
+        for from in 1..=count {
+            let height = ship.stack(from).unwrap().len();
+            for to in (1..=count).filter(|&to| to != from) {
+                for amount in 1..=height {
+                    // every state generated counts, not just the ones expanded
+                    self.nodes += 1;
+                    if self.nodes > self.budget {
+                        return None;
+                    }
+                    let instruction = Instruction { amount, from, to };
+                    let lifted = ship.stack(from).unwrap()[height - amount..].to_vec();
+                    self.crane.apply(ship, instruction).unwrap();
+                    self.path.push(instruction);
+
+                    // leave the path as it is once the search is over
+                    let found = self.run(ship, limit);
+                    if found != Some(false) {
+                        return found;
+                    }
+
+                    self.path.pop();
+                    let to_stack = ship.stack_mut(to).unwrap();
+                    to_stack.truncate(to_stack.len() - amount);
+                    ship.stack_mut(from).unwrap().extend(lifted);
+                }
+            }
+        }
+        Some(false)
+    }
+}
+
+// iterative deepening a* over stack states, so the first plan found is a
+// shortest one. `budget` caps the number of states generated.
+pub fn plan(
+    ship: &Ship,
+    crane: &dyn CrateMover,
+    target: &Target,
+    max_depth: usize,
+    budget: usize,
+) -> Outcome {
+    let mut ship = ship.clone();
+    let mut search = Search {
+        crane,
+        target,
+        path: vec![],
+        seen: HashMap::new(),
+        nodes: 0,
+        budget,
+    };
+    for limit in target.estimate(&ship)..=max_depth {
+        search.seen.clear();
+        match search.run(&mut ship, limit) {
+            Some(true) => return Outcome::Found(search.path),
+            Some(false) => {}
+            None => return Outcome::GaveUp,
+        }
+    }
+    Outcome::Exhausted
+}
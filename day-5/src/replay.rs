@@ -0,0 +1,131 @@
+use std::fmt;
+
+use crate::{
+    crane::{CrateMover, Instruction},
+    ship::Ship,
+};
+
+// an instruction that couldn't be carried out, and where it came from
+#[derive(Debug)]
+pub struct StepError {
+    pub line: usize,
+    pub instruction: Instruction,
+    pub message: &'static str,
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {}: {}",
+            self.line, self.instruction, self.message
+        )
+    }
+}
+
+// what a move took off the top of its `from` stack, bottom crate first
+#[derive(Debug)]
+struct Undo {
+    instruction: Instruction,
+    lifted: Vec<char>,
+}
+
+// runs a procedure one instruction at a time, keeping enough history to
+// step back to any earlier point
+pub struct Replay<'a> {
+    ship: Ship,
+    crane: &'a dyn CrateMover,
+    // input line number of each instruction
+    instructions: &'a [(usize, Instruction)],
+    undo: Vec<Undo>,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(
+        ship: Ship,
+        crane: &'a dyn CrateMover,
+        instructions: &'a [(usize, Instruction)],
+    ) -> Self {
+        Self {
+            ship,
+            crane,
+            instructions,
+            undo: vec![],
+        }
+    }
+
+    pub fn ship(&self) -> &Ship {
+        &self.ship
+    }
+
+    // how many instructions have been carried out
+    pub fn position(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn last(&self) -> Option<(usize, Instruction)> {
+        self.position()
+            .checked_sub(1)
+            .map(|index| self.instructions[index])
+    }
+
+    // carries out the next instruction, false once there are none left
+    pub fn step(&mut self) -> Result<bool, StepError> {
+        let Some(&(line, instruction)) = self.instructions.get(self.position()) else {
+            return Ok(false);
+        };
+        let lifted = self
+            .ship
+            .stack(instruction.from)
+            .map(|stack| stack[stack.len().saturating_sub(instruction.amount)..].to_vec())
+            .unwrap_or_default();
+        self.crane
+            .apply(&mut self.ship, instruction)
+            .map_err(|message| StepError {
+                line,
+                instruction,
+                message,
+            })?;
+        self.undo.push(Undo {
+            instruction,
+            lifted,
+        });
+        Ok(true)
+    }
+
+    // puts the last instruction's crates back, false at the start
+    pub fn back(&mut self) -> bool {
+        let Some(Undo {
+            instruction,
+            lifted,
+        }) = self.undo.pop()
+        else {
+            return false;
+        };
+        if instruction.from != instruction.to {
+            let to = self.ship.stack_mut(instruction.to).unwrap();
+            to.truncate(to.len() - lifted.len());
+            self.ship
+                .stack_mut(instruction.from)
+                .unwrap()
+                .extend(lifted);
+        }
+        true
+    }
+
+    // moves forwards or backwards until `position` instructions have been carried out
+    pub fn seek(&mut self, position: usize) -> Result<(), StepError> {
+        let position = position.min(self.len());
+        while self.position() > position {
+            self.back();
+        }
+        while self.position() < position {
+            self.step()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+
+// the stacks of crates on the ship, bottom crate first, in label order
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ship {
+    labels: Vec<usize>,
+    stacks: Vec<Vec<char>>,
+}
+
+impl Ship {
+    // reads the drawing from the top of the input, crate rows followed by the label row.
+    // every column is four characters wide: `[X] ` for a crate or four spaces.
+    pub fn parse(drawing: &str) -> Result<Self, String> {
+        let lines: Vec<&str> = drawing.lines().collect();
+        let Some((label_line, crate_lines)) = lines.split_last() else {
+            return Err(String::from("empty drawing"));
+        };
+        let label_number = lines.len();
+
+        let mut labels = vec![];
+        for (index, chunk) in columns(label_line).enumerate() {
+            let label: usize = chunk.trim().parse().map_err(|_| {
+                format!(
+                    "line {}: expected a label in column {}, found '{}'",
+                    label_number,
+                    index + 1,
+                    chunk.trim_end()
+                )
+            })?;
+            if label != index + 1 {
+                return Err(format!(
+                    "line {}: expected label {} in column {}, found {}",
+                    label_number,
+                    index + 1,
+                    index + 1,
+                    label
+                ));
+            }
+            labels.push(label);
+        }
+
+        let mut stacks = vec![vec![]; labels.len()];
+        // read bottom up so a crate can be checked against the one below it
+        for (number, line) in crate_lines.iter().enumerate().rev() {
+            let number = number + 1;
+            for (index, chunk) in columns(line).enumerate() {
+                let Some(stack) = stacks.get_mut(index) else {
+                    return Err(format!(
+                        "line {}: crate in column {} has no label",
+                        number,
+                        index + 1
+                    ));
+                };
+                if chunk.trim().is_empty() {
+                    continue;
+                }
+                let [b'[', c, b']', rest @ ..] = chunk.as_bytes() else {
+                    return Err(format!(
+                        "line {}: misaligned or malformed crate in column {}: '{}'",
+                        number,
+                        index + 1,
+                        chunk.trim_end()
+                    ));
+                };
+                if !c.is_ascii_alphabetic() || rest.iter().any(|b| *b != b' ') {
+                    return Err(format!(
+                        "line {}: misaligned or malformed crate in column {}: '{}'",
+                        number,
+                        index + 1,
+                        chunk.trim_end()
+                    ));
+                }
+                if stack.len() != crate_lines.len() - number {
+                    return Err(format!(
+                        "line {}: crate in column {} is floating",
+                        number,
+                        index + 1
+                    ));
+                }
+                stack.push(*c as char);
+            }
+        }
+
+        Ok(Self { labels, stacks })
+    }
+
+    pub fn stack_count(&self) -> usize {
+        self.stacks.len()
+    }
+
+    pub fn stack(&self, label: usize) -> Option<&Vec<char>> {
+        self.stacks.get(label.checked_sub(1)?)
+    }
+
+    pub fn stack_mut(&mut self, label: usize) -> Option<&mut Vec<char>> {
+        self.stacks.get_mut(label.checked_sub(1)?)
+    }
+
+    // two different stacks at once, so crates can move between them in place
+    pub fn stacks_mut(&mut self, a: usize, b: usize) -> Option<(&mut Vec<char>, &mut Vec<char>)> {
+        let (a, b) = (a.checked_sub(1)?, b.checked_sub(1)?);
+        if a == b || a >= self.stacks.len() || b >= self.stacks.len() {
+            return None;
+        }
+        if a < b {
+            let (low, high) = self.stacks.split_at_mut(b);
+            Some((&mut low[a], &mut high[0]))
+        } else {
+            let (low, high) = self.stacks.split_at_mut(a);
+            Some((&mut high[0], &mut low[b]))
+        }
+    }
+
+    pub fn tops(&self) -> String {
+        self.stacks
+            .iter()
+            .filter_map(|stack| stack.last())
+            .collect()
+    }
+}
+
+// splits a drawing line into four character columns, the last may be shorter
+fn columns(line: &str) -> impl Iterator<Item = &str> {
+    line.as_bytes()
+        .chunks(4)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or("?"))
+}
+
+// draws the ship exactly as the puzzle does, every row padded to full width
+impl fmt::Display for Ship {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let height = self.stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+        for row in (0..height).rev() {
+            let cells: Vec<String> = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(row) {
+                    Some(c) => format!("[{}]", c),
+                    None => String::from("   "),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" "))?;
+        }
+        let labels: Vec<String> = self
+            .labels
+            .iter()
+            .map(|label| format!(" {} ", label))
+            .collect();
+        write!(f, "{}", labels.join(" "))
+    }
+}
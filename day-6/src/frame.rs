@@ -0,0 +1,66 @@
+use std::{fmt::Write, ops::Range};
+
+use crate::marker::Detector;
+
+// a marker and the payload that follows it up to the next marker
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub marker: Range<usize>,
+    pub payload: Range<usize>,
+}
+
+impl Frame {
+    pub fn len(&self) -> usize {
+        self.payload.end - self.marker.start
+    }
+}
+
+// splits the datastream at markers of `size` distinct letters. a marker has to
+// lie wholly after the one before it, and anything ahead of the first marker
+// is returned as the preamble.
+pub fn decode(data: &[u8], size: usize) -> (Range<usize>, Vec<Frame>) {
+    let mut detector = Detector::new(size);
+    let mut frames: Vec<Frame> = vec![];
+    for &byte in data {
+        if !detector.push(byte) {
+            continue;
+        }
+        let end = detector.position();
+        let start = end - size;
+        if let Some(last) = frames.last_mut() {
+            last.payload.end = start;
+        }
+        frames.push(Frame {
+            marker: start..end,
+            payload: end..data.len(),
+        });
+        detector.reset();
+    }
+    let preamble = 0..frames
+        .first()
+        .map_or(data.len(), |frame| frame.marker.start);
+    (preamble, frames)
+}
+
+// sixteen bytes a line: offset, hex bytes and the printable characters
+pub fn hexdump(data: &[u8], offset: usize) -> String {
+    let mut out = String::new();
+    for (index, line) in data.chunks(16).enumerate() {
+        write!(out, "{:08x}  ", offset + index * 16).unwrap();
+        for column in 0..16 {
+            match line.get(column) {
+                Some(byte) => write!(out, "{:02x} ", byte).unwrap(),
+                None => out.push_str("   "),
+            }
+            if column == 7 {
+                out.push(' ');
+            }
+        }
+        let text: String = line
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() { b as char } else { '.' })
+            .collect();
+        writeln!(out, " |{}|", text).unwrap();
+    }
+    out
+}
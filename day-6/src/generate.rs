@@ -0,0 +1,109 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// xorshift64*, plenty for making up datastreams
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // the state must never be zero
+        Self(seed ^ 0x9e37_79b9_7f4a_7c15 | 1)
+    }
+
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self::new(nanos)
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // a number in 0..bound
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn letter(&mut self) -> u8 {
+        b'a' + self.below(26) as u8
+    }
+}
+
+// a random stream of `length` lowercase letters whose first window of `size`
+// distinct letters ends at `position`, the number the detector reports
+pub fn generate(
+    length: usize,
+    position: usize,
+    size: usize,
+    rng: &mut Rng,
+) -> Result<Vec<u8>, String> {
+    if size == 0 || size > 26 {
+        return Err(format!("no marker can be {} distinct letters long", size));
+    }
+    if position < size || position > length {
+        return Err(format!(
+            "a marker of {} ending at {} doesn't fit in {} letters",
+            size, position, length
+        ));
+    }
+    if size == 1 && position != 1 {
+        return Err(String::from("every single letter is a marker of 1"));
+    }
+
+    // the marker itself: `size` different letters
+    let mut marker: Vec<u8> = (b'a'..=b'z').collect();
+    for i in 0..size {
+        marker.swap(i, i + rng.below(26 - i));
+    }
+    marker.truncate(size);
+
+    let start = position - size;
+    let mut data = Vec::with_capacity(length);
+    if start > 0 {
+        // every window running into the marker picks up the letter just
+        // before it, so that letter repeats the marker's first one
+        let mut prefix = prefix(start - 1, size, rng, marker[0]);
+        prefix.push(marker[0]);
+        data.extend(prefix);
+    }
+    data.extend(&marker);
+    data.extend((position..length).map(|_| rng.letter()));
+    Ok(data)
+}
+
+// letters with no window of `size` distinct ones, even counting `next` on the end
+fn prefix(length: usize, size: usize, rng: &mut Rng, next: u8) -> Vec<u8> {
+    if size == 2 {
+        // only runs of one letter have no two different neighbours
+        return vec![next; length];
+    }
+    // repeating a letter within every `size - 1` letters also covers the
+    // window that ends with `next`
+    let span = size - 1;
+    let mut data: Vec<u8> = Vec::with_capacity(length + 1);
+    for i in 0..length {
+        let mut c = rng.letter();
+        if i + 1 >= span {
+            let window = &data[i + 1 - span..];
+            if distinct(window) && !window.contains(&c) {
+                c = window[rng.below(window.len())];
+            }
+        }
+        data.push(c);
+    }
+    data
+}
+
+fn distinct(letters: &[u8]) -> bool {
+    let mut seen = 0u32;
+    letters.iter().all(|&c| {
+        let bit = 1 << (c - b'a');
+        let new = seen & bit == 0;
+        seen |= bit;
+        new
+    })
+}
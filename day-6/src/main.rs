@@ -1,23 +1,161 @@
-use std::{collections::HashSet, fs::read_to_string};
-
-fn find_marker(input: &Vec<&str>, size: usize) {
-    for (index, window) in input.windows(size).skip(1).enumerate() {
-        let set: HashSet<&str> = HashSet::from_iter(window.iter().map(|i| i.to_owned()));
-        if set.len() == window.len() {
-            println!("{} uniques - {:?} — {}", size, window, index + size);
-            break;
-        }
+use std::{
+    env, fs,
+    fs::File,
+    io::{self, BufReader, Write},
+};
+
+mod frame;
+mod generate;
+mod marker;
+
+use generate::Rng;
+
+use marker::{find_marker, find_markers};
+
+fn report(size: usize) {
+    let file = File::open("./input.txt").unwrap();
+    match find_marker(BufReader::new(file), size) {
+        Ok(Some(position)) => println!("{} uniques — {}", size, position),
+        Ok(None) => println!("{} uniques — no marker", size),
+        Err(e) => fail(e),
     }
 }
 
 fn main() {
-    let input = read_to_string("./input.txt").unwrap();
+    let args: Vec<String> = env::args().skip(1).collect();
 
-    let input: Vec<&str> = input.split("").collect();
+    match args.first().map(|arg| arg.as_str()) {
+        Some("markers") => return markers(),
+        Some("frames") => return frames(&args),
+        Some("generate") => return generate(&args),
+        Some("fuzz") => return fuzz(&args),
+        _ => {}
+    }
 
     // part 1
-    find_marker(&input, 4);
+    report(4);
 
     // part 2
-    find_marker(&input, 14);
+    report(14);
+
+    // `--window N` looks for a marker of any other length
+    if let Some(size) = option(&args, "--window") {
+        report(size.parse().unwrap());
+    }
+}
+
+// every start-of-packet and start-of-message marker, by the position just after it
+fn markers() {
+    for (name, size) in [("start-of-packet", 4), ("start-of-message", 14)] {
+        let file = File::open("./input.txt").unwrap();
+        let positions = find_markers(BufReader::new(file), size).unwrap();
+        let positions: Vec<String> = positions.iter().map(|p| p.to_string()).collect();
+        println!("{} markers: {}", name, positions.len());
+        println!("{}", positions.join(" "));
+    }
+}
+
+// splits the stream into frames at start-of-packet markers, or at
+// start-of-message markers with `--message`
+fn frames(args: &[String]) {
+    let data = fs::read("./input.txt").unwrap();
+    let data = data.trim_ascii_end();
+    let size = if args.iter().any(|arg| arg == "--message") {
+        14
+    } else {
+        4
+    };
+    let hexdump = args.iter().any(|arg| arg == "--hexdump");
+
+    let (preamble, frames) = frame::decode(data, size);
+    println!("preamble: {} bytes", preamble.len());
+    if hexdump {
+        print!(
+            "{}",
+            frame::hexdump(&data[preamble.clone()], preamble.start)
+        );
+    }
+    for (index, frame) in frames.iter().enumerate() {
+        println!(
+            "frame {}: offset {}, marker {}, payload {} bytes, {} bytes in all",
+            index + 1,
+            frame.marker.start,
+            String::from_utf8_lossy(&data[frame.marker.clone()]),
+            frame.payload.len(),
+            frame.len()
+        );
+        if hexdump {
+            print!(
+                "{}",
+                frame::hexdump(&data[frame.payload.clone()], frame.payload.start)
+            );
+        }
+    }
+}
+
+fn rng(args: &[String]) -> Rng {
+    match option(args, "--seed") {
+        Some(seed) => Rng::new(seed.parse().unwrap()),
+        None => Rng::from_time(),
+    }
+}
+
+// `generate <length> <position> [--size N] [--seed S]` prints a stream whose
+// first marker of N letters, 4 by default, is reported at `position`
+fn generate(args: &[String]) {
+    let (Some(length), Some(position)) = (args.get(1), args.get(2)) else {
+        fail("usage: generate <length> <position> [--size N] [--seed S]");
+    };
+    let size = option(args, "--size").map_or(4, |size| size.parse().unwrap());
+    let data = generate::generate(
+        length.parse().unwrap(),
+        position.parse().unwrap(),
+        size,
+        &mut rng(args),
+    )
+    .unwrap_or_else(|e| fail(e));
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(&data).unwrap();
+    stdout.write_all(b"\n").unwrap();
+}
+
+// plants markers in random streams and checks the detector and the frame
+// decoder both find them where they were put
+fn fuzz(args: &[String]) {
+    let trials = option(args, "--trials").map_or(10_000, |n| n.parse().unwrap());
+    let mut rng = rng(args);
+    for trial in 1..=trials {
+        let size = 2 + rng.below(25);
+        let position = size + rng.below(200);
+        let length = position + rng.below(50);
+        let data = generate::generate(length, position, size, &mut rng).unwrap();
+
+        let found = find_marker(&data[..], size).unwrap();
+        let (_, frames) = frame::decode(&data, size);
+        let decoded = frames.first().map(|frame| frame.marker.end);
+        if found != Some(position) || decoded != Some(position) {
+            fail(format!(
+                "trial {}: marker of {} planted at {} but found at {:?} and decoded at {:?} in {}",
+                trial,
+                size,
+                position,
+                found,
+                decoded,
+                String::from_utf8_lossy(&data)
+            ));
+        }
+    }
+    println!("{} planted markers all found", trials);
+}
+
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn option<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|value| value.as_str())
 }
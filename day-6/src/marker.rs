@@ -0,0 +1,134 @@
+use std::io::{self, Read};
+
+// the last `size` bytes of a stream and how many distinct letters they hold,
+// updated in constant time per byte
+pub struct Detector {
+    size: usize,
+    // ring buffer of the bytes in the window
+    window: Vec<u8>,
+    counts: [usize; 26],
+    distinct: usize,
+    // bytes in the window that aren't lowercase letters, so can't be part of a marker
+    others: usize,
+    position: usize,
+    // where the window was last reset
+    start: usize,
+}
+
+impl Detector {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            window: vec![0; size],
+            counts: [0; 26],
+            distinct: 0,
+            others: 0,
+            position: 0,
+            start: 0,
+        }
+    }
+
+    // forgets the window, so the next marker can't overlap the last one
+    pub fn reset(&mut self) {
+        self.counts = [0; 26];
+        self.distinct = 0;
+        self.others = 0;
+        self.start = self.position;
+    }
+
+    // how many bytes have been pushed
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    // adds the next byte, true if the last `size` bytes are all different
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.size == 0 {
+            self.position += 1;
+            return true;
+        }
+        let seen = self.position - self.start;
+        let slot = seen % self.size;
+        if seen >= self.size {
+            self.remove(self.window[slot]);
+        }
+        self.window[slot] = byte;
+        self.add(byte);
+        self.position += 1;
+        seen + 1 >= self.size && self.others == 0 && self.distinct == self.size
+    }
+
+    fn add(&mut self, byte: u8) {
+        match letter(byte) {
+            Some(index) => {
+                self.counts[index] += 1;
+                if self.counts[index] == 1 {
+                    self.distinct += 1;
+                }
+            }
+            None => self.others += 1,
+        }
+    }
+
+    fn remove(&mut self, byte: u8) {
+        match letter(byte) {
+            Some(index) => {
+                self.counts[index] -= 1;
+                if self.counts[index] == 0 {
+                    self.distinct -= 1;
+                }
+            }
+            None => self.others -= 1,
+        }
+    }
+}
+
+fn letter(byte: u8) -> Option<usize> {
+    byte.is_ascii_lowercase().then(|| (byte - b'a') as usize)
+}
+
+// number of characters read up to and including the first marker of `size`
+// distinct letters
+pub fn find_marker(reader: impl Read, size: usize) -> io::Result<Option<usize>> {
+    let mut first = None;
+    scan(reader, size, |position| {
+        first = Some(position);
+        false
+    })?;
+    Ok(first)
+}
+
+// the end position of every window of `size` distinct letters, overlapping
+// ones included
+pub fn find_markers(reader: impl Read, size: usize) -> io::Result<Vec<usize>> {
+    let mut all = vec![];
+    scan(reader, size, |position| {
+        all.push(position);
+        true
+    })?;
+    Ok(all)
+}
+
+// feeds the stream through a detector a chunk at a time, calling `found` at
+// each marker until it returns false
+fn scan(
+    mut reader: impl Read,
+    size: usize,
+    mut found: impl FnMut(usize) -> bool,
+) -> io::Result<()> {
+    let mut detector = Detector::new(size);
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        for &byte in &buffer[..read] {
+            if detector.push(byte) && !found(detector.position()) {
+                return Ok(());
+            }
+        }
+    }
+}
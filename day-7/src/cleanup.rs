@@ -0,0 +1,153 @@
+use std::cmp::Reverse;
+
+use crate::fs::{Filesystem, NodeId};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Goal {
+    // free the space deleting as little as possible
+    LeastData,
+    // free the space deleting as few files and directories as possible
+    FewestItems,
+}
+
+pub struct Cleanup {
+    pub deleted: Vec<NodeId>,
+    pub freed: u64,
+    // false if the search ran out of budget, so a better choice may exist
+    pub optimal: bool,
+}
+
+pub enum Outcome {
+    Found(Cleanup),
+    // even deleting everything that may be deleted frees only this much
+    Impossible(u64),
+    // ran out of budget before finding any choice that frees enough
+    GaveUp,
+}
+
+// something that could be deleted, and where the things inside it end
+struct Item {
+    id: NodeId,
+    size: u64,
+    end: usize,
+}
+
+struct Search<'a> {
+    items: &'a [Item],
+    // the most that can be freed from each item on: the item itself and
+    // the best from whatever comes after its subtree
+    reach: Vec<u64>,
+    required: u64,
+    goal: Goal,
+    chosen: Vec<usize>,
+    best: Option<((u64, u64), Vec<usize>)>,
+    nodes: usize,
+    budget: usize,
+}
+
+impl Search<'_> {
+    // what the goal minimises first, then second
+    fn key(&self, freed: u64, count: usize) -> (u64, u64) {
+        match self.goal {
+            Goal::LeastData => (freed, count as u64),
+            Goal::FewestItems => (count as u64, freed),
+        }
+    }
+
+    fn beaten(&self, key: (u64, u64)) -> bool {
+        self.best.as_ref().is_some_and(|(best, _)| key >= *best)
+    }
+
+    // false once the budget is spent
+    fn run(&mut self, index: usize, freed: u64) -> bool {
+        let count = self.chosen.len();
+        if freed >= self.required {
+            let key = self.key(freed, count);
+            if !self.beaten(key) {
+                self.best = Some((key, self.chosen.clone()));
+            }
+            return true;
+        }
+        // at least one more item, freeing at least what's still missing
+        if index == self.items.len()
+            || freed + self.reach[index] < self.required
+            || self.beaten(self.key(self.required, count + 1))
+        {
+            return true;
+        }
+        self.nodes += 1;
+        if self.nodes > self.budget {
+            return false;
+        }
+
+        let item = &self.items[index];
+        let (size, end) = (item.size, item.end);
+        self.chosen.push(index);
+        let finished = self.run(end, freed + size);
+        self.chosen.pop();
+        finished && self.run(index + 1, freed)
+    }
+}
+
+// picks files and directories, none inside another, to delete so at least
+// `required` bytes are freed
+pub fn plan(
+    filesystem: &Filesystem,
+    required: u64,
+    goal: Goal,
+    dirs_only: bool,
+    budget: usize,
+) -> Outcome {
+    let mut items = vec![];
+    collect(filesystem, filesystem.root(), dirs_only, &mut items);
+
+    let mut reach = vec![0; items.len() + 1];
+    for index in (0..items.len()).rev() {
+        reach[index] = items[index].size + reach[items[index].end];
+    }
+    if reach[0] < required {
+        return Outcome::Impossible(reach[0]);
+    }
+
+    let mut search = Search {
+        items: &items,
+        reach,
+        required,
+        goal,
+        chosen: vec![],
+        best: None,
+        nodes: 0,
+        budget,
+    };
+    let optimal = search.run(0, 0);
+    let Some((_, chosen)) = search.best else {
+        return Outcome::GaveUp;
+    };
+    let deleted: Vec<NodeId> = chosen.iter().map(|&index| items[index].id).collect();
+    let freed = deleted.iter().map(|&id| filesystem.size(id)).sum();
+    Outcome::Found(Cleanup {
+        deleted,
+        freed,
+        optimal,
+    })
+}
+
+// everything below `dir` in preorder, biggest first so good choices turn up early
+fn collect(filesystem: &Filesystem, dir: NodeId, dirs_only: bool, items: &mut Vec<Item>) {
+    let mut children = filesystem.children(dir).to_vec();
+    children.sort_by_key(|&child| Reverse(filesystem.size(child)));
+    for child in children {
+        let node = filesystem.node(child);
+        if dirs_only && !node.is_dir() {
+            continue;
+        }
+        let index = items.len();
+        items.push(Item {
+            id: child,
+            size: node.size,
+            end: 0,
+        });
+        collect(filesystem, child, dirs_only, items);
+        items[index].end = items.len();
+    }
+}
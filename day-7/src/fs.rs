@@ -0,0 +1,163 @@
+use std::fmt::Write;
+
+// index of a node in the filesystem's arena
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+#[derive(Debug)]
+pub enum Kind {
+    Dir(Vec<NodeId>),
+    File,
+}
+
+#[derive(Debug)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: Kind,
+    // a file's own size, or everything below a directory
+    pub size: u64,
+}
+
+impl Node {
+    pub fn is_dir(&self) -> bool {
+        matches!(self.kind, Kind::Dir(_))
+    }
+}
+
+// the directory tree the transcript explores, every node owned by one vector
+#[derive(Debug)]
+pub struct Filesystem {
+    nodes: Vec<Node>,
+}
+
+impl Filesystem {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node {
+                name: String::from("/"),
+                parent: None,
+                kind: Kind::Dir(vec![]),
+                size: 0,
+            }],
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    pub fn size(&self, id: NodeId) -> u64 {
+        self.node(id).size
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.node(id).kind {
+            Kind::Dir(children) => children,
+            Kind::File => &[],
+        }
+    }
+
+    pub fn child(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        self.children(dir)
+            .iter()
+            .copied()
+            .find(|&child| self.node(child).name == name)
+    }
+
+    // the directory called `name` in `dir`, made if it isn't there yet
+    pub fn add_dir(&mut self, dir: NodeId, name: &str) -> NodeId {
+        if let Some(existing) = self.child(dir, name) {
+            return existing;
+        }
+        self.insert(dir, name, Kind::Dir(vec![]), 0)
+    }
+
+    // the file called `name` in `dir`, made if it isn't there yet
+    pub fn add_file(&mut self, dir: NodeId, name: &str, size: u64) -> NodeId {
+        if let Some(existing) = self.child(dir, name) {
+            return existing;
+        }
+        self.insert(dir, name, Kind::File, size)
+    }
+
+    fn insert(&mut self, dir: NodeId, name: &str, kind: Kind, size: u64) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            name: name.to_owned(),
+            parent: Some(dir),
+            kind,
+            size,
+        });
+        if let Kind::Dir(children) = &mut self.nodes[dir.0].kind {
+            children.push(id);
+        }
+        // keep every directory's total up to date
+        let mut ancestor = Some(dir);
+        while let Some(current) = ancestor {
+            self.nodes[current.0].size += size;
+            ancestor = self.nodes[current.0].parent;
+        }
+        id
+    }
+
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = vec![];
+        let mut current = id;
+        while let Some(parent) = self.node(current).parent {
+            names.push(self.node(current).name.as_str());
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    // every node below and including `id` with its depth, parents first
+    pub fn walk(&self, id: NodeId) -> Vec<(NodeId, usize)> {
+        let mut out = vec![];
+        let mut pending = vec![(id, 0)];
+        while let Some((current, depth)) = pending.pop() {
+            out.push((current, depth));
+            for &child in self.children(current).iter().rev() {
+                pending.push((child, depth + 1));
+            }
+        }
+        out
+    }
+
+    pub fn dirs(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len())
+            .map(NodeId)
+            .filter(|&id| self.node(id).is_dir())
+    }
+
+    // like `du`: each directory's total after everything inside it
+    pub fn render_du(&self) -> String {
+        let mut out = String::new();
+        for (id, _) in self.walk(self.root()).iter().rev() {
+            if self.node(*id).is_dir() {
+                writeln!(out, "{}\t{}", self.size(*id), self.path(*id)).unwrap();
+            }
+        }
+        out
+    }
+
+    // the listing from the puzzle text with directory totals added, children
+    // in the order they were seen
+    pub fn render_tree(&self) -> String {
+        let mut out = String::new();
+        for (id, depth) in self.walk(self.root()) {
+            let node = self.node(id);
+            let detail = match node.kind {
+                Kind::Dir(_) => format!("dir, size={}", node.size),
+                Kind::File => format!("file, size={}", node.size),
+            };
+            writeln!(out, "{}- {} ({})", "  ".repeat(depth), node.name, detail).unwrap();
+        }
+        out
+    }
+}
@@ -1,6 +1,20 @@
-use std::{collections::HashMap, fs::read_to_string};
+use std::{env, fs::read_to_string, path::Path};
 
-const MAX_SIZE: u32 = 100_000;
+mod cleanup;
+mod fs;
+mod query;
+mod replay;
+mod transcript;
+
+use cleanup::{Goal, Outcome};
+use fs::Filesystem;
+use query::{Answer, Query};
+use replay::Replay;
+use transcript::{Order, Sort};
+
+const MAX_SIZE: u64 = 100_000;
+const CAPACITY: u64 = 70_000_000;
+const NEEDED: u64 = 30_000_000;
 
 #[derive(Debug)]
 enum Move<'a> {
@@ -25,8 +39,8 @@ impl<'a> From<&'a str> for Move<'a> {
 enum InputLine<'a> {
     Move(Move<'a>),
     List,
-    Dir,
-    File(u32),
+    Dir(&'a str),
+    File(u64, &'a str),
 }
 
 impl<'a> TryFrom<&'a str> for InputLine<'a> {
@@ -34,89 +48,248 @@ impl<'a> TryFrom<&'a str> for InputLine<'a> {
 
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
         if value.starts_with("$ cd") {
-            let split: Vec<&str> = value.split(" ").collect();
-            if let Some(dir) = split.get(2) {
-                return Ok(Self::Move(Move::from(dir.to_owned())));
-            } else {
-                return Err("cannot parse 'cd' command");
-            }
+            // everything after `cd `, so names may hold spaces
+            return match value.strip_prefix("$ cd ") {
+                Some(dir) if !dir.is_empty() => Ok(Self::Move(Move::from(dir))),
+                _ => Err("cannot parse 'cd' command"),
+            };
         }
         if value.starts_with("$ ls") {
             return Ok(Self::List);
         }
-        if value.starts_with("dir") {
-            return Ok(Self::Dir);
+        if let Some(name) = value.strip_prefix("dir ") {
+            return Ok(Self::Dir(name));
         }
-        let split: Vec<&str> = value.split(" ").collect();
-        let size = split.get(0);
-        if let Ok(size) = size.unwrap().parse() {
-            return Ok(Self::File(size));
-        } else {
-            return Err("cannot parse size of file line");
+        let Some((size, name)) = value.split_once(' ') else {
+            return Err("cannot parse file line");
+        };
+        match size.parse() {
+            Ok(size) => Ok(Self::File(size, name)),
+            Err(_) => Err("cannot parse size of file line"),
         }
     }
 }
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if command(&args) == Some("transcript") {
+        transcript(&args);
+        return;
+    }
+
     let input = read_to_string("./input.txt").unwrap();
-    let inputs: Vec<InputLine> = input
-        .lines()
-        .map(|line| {
-            return InputLine::try_from(line).unwrap();
-        })
-        .collect();
-
-    let mut current_path: Vec<&str> = vec![];
-    let mut dir_sizes: HashMap<String, u32> = HashMap::new();
-    // use instructions to build filesystem
-    for input in inputs {
-        match input {
-            InputLine::Move(dir) => match dir {
-                Move::Into(dir) => {
-                    current_path.push(dir);
-                    let path = current_path.join("/");
-                    if !dir_sizes.contains_key(&path) {
-                        dir_sizes.insert(path, 0);
-                    }
-                }
-                Move::ToParent => {
-                    current_path.pop();
-                }
-                Move::ToRoot => {
-                    current_path.push("<root>");
-                    let path = current_path.join("/");
-                    if !dir_sizes.contains_key(&path) {
-                        dir_sizes.insert(path, 0);
-                    }
-                }
-            },
-            InputLine::Dir => {}
-            InputLine::File(filesize) => {
-                let mut update_list: Vec<String> = vec![];
-                for dir in &current_path {
-                    update_list.push(dir.to_string());
-                    let update_path = update_list.join("/");
-                    dir_sizes
-                        .entry(update_path)
-                        .and_modify(|size| *size += filesize);
-                }
-            }
-            _ => {}
-        }
+    let (filesystem, warnings) = Replay::run(&input);
+    for warning in &warnings {
+        eprintln!("{}", warning);
+    }
+
+    match command(&args) {
+        Some("du") => return print!("{}", filesystem.render_du()),
+        Some("tree") => return print!("{}", filesystem.render_tree()),
+        _ => {}
+    }
+
+    // `--capacity` and `--needed` describe some other disk and update
+    let capacity = option(&args, "--capacity").map_or(CAPACITY, |n| n.parse().unwrap());
+    let needed = option(&args, "--needed").map_or(NEEDED, |n| n.parse().unwrap());
+    let used = filesystem.size(filesystem.root());
+    let space_needed = (needed + used).saturating_sub(capacity);
+
+    if command(&args) == Some("cleanup") {
+        cleanup(&filesystem, &args, capacity, needed, space_needed);
+        return;
+    }
+
+    // `find <terms>` answers any other question the same way
+    if command(&args) == Some("find") {
+        find(&filesystem, &operands(&args)[1..].join(" "));
+        return;
     }
 
     // part 1
-    let total: u32 = dir_sizes.values().filter(|&size| *size < MAX_SIZE).sum();
-    println!("total size: {}", total);
+    let total = ask(&filesystem, &format!("type=dir size<{} sum", MAX_SIZE));
+    println!("total size: {}", total.unwrap_or(0));
 
     // part 2
-    let root = String::from("<root>");
-    let space_needed: u32 = 30_000_000 - (70_000_000 - dir_sizes.get(&root).unwrap());
-    let candidate = dir_sizes
-        .iter()
-        .filter(|(_, &size)| size >= space_needed)
-        .min_by(|a, b| a.1.cmp(&b.1))
-        .unwrap();
-    let candidate_size = candidate.1;
-    println!("candidate size: {}", candidate_size);
+    match ask(&filesystem, &format!("type=dir size>={} min", space_needed)) {
+        Some(candidate_size) => println!("candidate size: {}", candidate_size),
+        None => println!("no candidate frees enough"),
+    }
+}
+
+fn parse_query(query: &str) -> Query {
+    Query::parse(query).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    })
+}
+
+// the value of a query ending in an aggregate
+fn ask(filesystem: &Filesystem, query: &str) -> Option<u64> {
+    match parse_query(query).run(filesystem) {
+        Answer::Value(value) => value,
+        Answer::Rows(_) => unreachable!("'{}' has no aggregate", query),
+    }
+}
+
+fn find(filesystem: &Filesystem, query: &str) {
+    match parse_query(query).run(filesystem) {
+        Answer::Rows(rows) => {
+            for (id, _) in rows {
+                let kind = if filesystem.node(id).is_dir() {
+                    "dir "
+                } else {
+                    "file"
+                };
+                println!(
+                    "{} {:>10} {}",
+                    kind,
+                    filesystem.size(id),
+                    filesystem.path(id)
+                );
+            }
+        }
+        Answer::Value(Some(value)) => println!("{}", value),
+        Answer::Value(None) => println!("nothing matched"),
+    }
+}
+
+// `cleanup [--fewest] [--dirs-only] [--budget N]` deletes enough to make room
+// for the update, as little data as possible unless `--fewest` asks for as few
+// deletions as possible instead
+fn cleanup(filesystem: &Filesystem, args: &[String], capacity: u64, needed: u64, required: u64) {
+    let flag = |name: &str| args.iter().any(|arg| arg == name);
+    let goal = if flag("--fewest") {
+        Goal::FewestItems
+    } else {
+        Goal::LeastData
+    };
+    let budget = option(args, "--budget").map_or(1_000_000, |n| n.parse().unwrap());
+    let used = filesystem.size(filesystem.root());
+
+    if needed > capacity {
+        eprintln!(
+            "the disk only holds {}, {} can never be free",
+            capacity, needed
+        );
+        std::process::exit(1);
+    }
+    if required == 0 {
+        println!(
+            "{} of {} free already",
+            capacity.saturating_sub(used),
+            capacity
+        );
+        return;
+    }
+    let plan = match cleanup::plan(filesystem, required, goal, flag("--dirs-only"), budget) {
+        Outcome::Found(plan) => plan,
+        Outcome::Impossible(most) => {
+            eprintln!(
+                "deleting everything frees only {} of the {} needed",
+                most, required
+            );
+            std::process::exit(1);
+        }
+        Outcome::GaveUp => {
+            eprintln!(
+                "gave up after {} states without finding {} to free",
+                budget, required
+            );
+            std::process::exit(1);
+        }
+    };
+
+    for &id in &plan.deleted {
+        let kind = if filesystem.node(id).is_dir() {
+            "dir "
+        } else {
+            "file"
+        };
+        println!(
+            "{} {:>10} {}",
+            kind,
+            filesystem.size(id),
+            filesystem.path(id)
+        );
+    }
+    println!(
+        "{} deleted, {} freed, {} of {} free for the {} needed",
+        plan.deleted.len(),
+        plan.freed,
+        (capacity + plan.freed).saturating_sub(used),
+        capacity,
+        needed
+    );
+    if !plan.optimal {
+        println!("search budget spent, there may be a better choice");
+    }
+}
+
+// `transcript <dir> [--order depth|breadth] [--sort name|size] [--revisit N]
+// [--seed S]` prints a session exploring a real directory, to use as input
+fn transcript(args: &[String]) {
+    let Some(dir) = operands(args).get(1).copied() else {
+        eprintln!("usage: transcript <dir> [--order depth|breadth] [--sort name|size] [--revisit N] [--seed S]");
+        std::process::exit(1);
+    };
+    let order = match option(args, "--order") {
+        Some("breadth") => Order::BreadthFirst,
+        _ => Order::DepthFirst,
+    };
+    let sort = match option(args, "--sort") {
+        Some("name") => Sort::Name,
+        Some("size") => Sort::Size,
+        _ => Sort::Listing,
+    };
+    let revisit = option(args, "--revisit").map_or(0, |n| n.parse().unwrap());
+    let seed = option(args, "--seed").map(|n| n.parse().unwrap());
+
+    let filesystem = match transcript::scan(Path::new(dir)) {
+        Ok(filesystem) => filesystem,
+        Err(e) => {
+            eprintln!("{}: {}", dir, e);
+            std::process::exit(1);
+        }
+    };
+    print!(
+        "{}",
+        transcript::transcript(&filesystem, order, sort, revisit, seed)
+    );
+    eprintln!(
+        "{} directories, {} bytes in all",
+        filesystem.dirs().count(),
+        filesystem.size(filesystem.root())
+    );
+}
+
+// flags that stand alone rather than taking a value
+const SWITCHES: [&str; 2] = ["--fewest", "--dirs-only"];
+
+// arguments that aren't a `--flag` or its value
+fn operands(args: &[String]) -> Vec<&str> {
+    let mut operands = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--") {
+            if !SWITCHES.contains(&arg.as_str()) {
+                args.next();
+            }
+        } else {
+            operands.push(arg.as_str());
+        }
+    }
+    operands
+}
+
+// first argument that isn't a `--flag` or its value
+fn command(args: &[String]) -> Option<&str> {
+    operands(args).first().copied()
+}
+
+fn option<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|value| value.as_str())
 }
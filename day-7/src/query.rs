@@ -0,0 +1,209 @@
+use std::cmp::Ordering;
+
+use crate::fs::{Filesystem, NodeId};
+
+#[derive(Clone, Copy, Debug)]
+enum Cmp {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl Cmp {
+    fn holds(self, value: u64, bound: u64) -> bool {
+        match self {
+            Cmp::Lt => value < bound,
+            Cmp::Le => value <= bound,
+            Cmp::Eq => value == bound,
+            Cmp::Ge => value >= bound,
+            Cmp::Gt => value > bound,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Filter {
+    Name(String),
+    Dir(bool),
+    Size(Cmp, u64),
+    Depth(Cmp, u64),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Key {
+    Size,
+    Name,
+    Path,
+    Depth,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Aggregate {
+    Sum,
+    Count,
+    Min,
+    Max,
+}
+
+// a find-style question about the filesystem, written as terms like
+// `type=dir size<100000 sum` or `name=*.txt sort=-size limit=5`
+#[derive(Debug)]
+pub struct Query {
+    filters: Vec<Filter>,
+    // the key and whether it's descending
+    sort: Option<(Key, bool)>,
+    limit: Option<usize>,
+    aggregate: Option<Aggregate>,
+}
+
+pub enum Answer {
+    // matching nodes with their depth
+    Rows(Vec<(NodeId, usize)>),
+    // the aggregate of the matching sizes, `None` when nothing matched
+    Value(Option<u64>),
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let mut parsed = Self {
+            filters: vec![],
+            sort: None,
+            limit: None,
+            aggregate: None,
+        };
+        for term in query.split_whitespace() {
+            let aggregate = match term {
+                "sum" => Some(Aggregate::Sum),
+                "count" => Some(Aggregate::Count),
+                "min" => Some(Aggregate::Min),
+                "max" => Some(Aggregate::Max),
+                _ => None,
+            };
+            if aggregate.is_some() {
+                parsed.aggregate = aggregate;
+                continue;
+            }
+            let Some(split) = term.find(['<', '>', '=']) else {
+                return Err(format!("'{}' is not a query term", term));
+            };
+            let key = &term[..split];
+            let (cmp, value) = match &term[split..] {
+                rest if rest.starts_with("<=") => (Cmp::Le, &rest[2..]),
+                rest if rest.starts_with(">=") => (Cmp::Ge, &rest[2..]),
+                rest if rest.starts_with('<') => (Cmp::Lt, &rest[1..]),
+                rest if rest.starts_with('>') => (Cmp::Gt, &rest[1..]),
+                rest => (Cmp::Eq, &rest[1..]),
+            };
+            let number = || {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| format!("'{}' needs a number", term))
+            };
+            let exact = || match cmp {
+                Cmp::Eq => Ok(()),
+                _ => Err(format!("'{}' can only be compared with =", key)),
+            };
+            match key {
+                "size" => parsed.filters.push(Filter::Size(cmp, number()?)),
+                "depth" => parsed.filters.push(Filter::Depth(cmp, number()?)),
+                "name" => {
+                    exact()?;
+                    parsed.filters.push(Filter::Name(value.to_owned()));
+                }
+                "type" => {
+                    exact()?;
+                    let dir = match value {
+                        "dir" | "d" => true,
+                        "file" | "f" => false,
+                        _ => return Err(format!("'{}' is not dir or file", value)),
+                    };
+                    parsed.filters.push(Filter::Dir(dir));
+                }
+                "sort" => {
+                    exact()?;
+                    let (descending, value) = match value.strip_prefix('-') {
+                        Some(value) => (true, value),
+                        None => (false, value),
+                    };
+                    let key = match value {
+                        "size" => Key::Size,
+                        "name" => Key::Name,
+                        "path" => Key::Path,
+                        "depth" => Key::Depth,
+                        _ => return Err(format!("can't sort by '{}'", value)),
+                    };
+                    parsed.sort = Some((key, descending));
+                }
+                "limit" => {
+                    exact()?;
+                    parsed.limit = Some(number()? as usize);
+                }
+                _ => return Err(format!("unknown key '{}'", key)),
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub fn run(&self, filesystem: &Filesystem) -> Answer {
+        let mut rows: Vec<(NodeId, usize)> = filesystem
+            .walk(filesystem.root())
+            .into_iter()
+            .filter(|&(id, depth)| self.matches(filesystem, id, depth))
+            .collect();
+
+        if let Some((key, descending)) = self.sort {
+            let order = |a: &(NodeId, usize), b: &(NodeId, usize)| -> Ordering {
+                let (x, y) = (filesystem.node(a.0), filesystem.node(b.0));
+                match key {
+                    Key::Size => x.size.cmp(&y.size),
+                    Key::Name => x.name.cmp(&y.name),
+                    Key::Path => filesystem.path(a.0).cmp(&filesystem.path(b.0)),
+                    Key::Depth => a.1.cmp(&b.1),
+                }
+            };
+            if descending {
+                rows.sort_by(|a, b| order(b, a));
+            } else {
+                rows.sort_by(order);
+            }
+        }
+        if let Some(limit) = self.limit {
+            rows.truncate(limit);
+        }
+
+        let sizes = rows.iter().map(|&(id, _)| filesystem.size(id));
+        match self.aggregate {
+            None => Answer::Rows(rows),
+            Some(Aggregate::Sum) => Answer::Value(Some(sizes.sum())),
+            Some(Aggregate::Count) => Answer::Value(Some(rows.len() as u64)),
+            Some(Aggregate::Min) => Answer::Value(sizes.min()),
+            Some(Aggregate::Max) => Answer::Value(sizes.max()),
+        }
+    }
+
+    fn matches(&self, filesystem: &Filesystem, id: NodeId, depth: usize) -> bool {
+        let node = filesystem.node(id);
+        self.filters.iter().all(|filter| match filter {
+            Filter::Name(pattern) => {
+                let pattern: Vec<char> = pattern.chars().collect();
+                let name: Vec<char> = node.name.chars().collect();
+                glob(&pattern, &name)
+            }
+            Filter::Dir(dir) => node.is_dir() == *dir,
+            Filter::Size(cmp, bound) => cmp.holds(node.size, *bound),
+            Filter::Depth(cmp, bound) => cmp.holds(depth as u64, *bound),
+        })
+    }
+}
+
+// `*` matches any run of characters and `?` any one
+fn glob(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| glob(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && glob(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && glob(rest, &name[1..]),
+    }
+}
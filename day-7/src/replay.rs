@@ -0,0 +1,200 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    fs::{Filesystem, NodeId},
+    InputLine, Move,
+};
+
+// something in the transcript that doesn't add up, and where
+#[derive(Debug)]
+pub struct Warning {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+// the `ls` whose output is being read
+struct Listing {
+    line: usize,
+    dir: NodeId,
+    // whether an earlier `ls` already showed this directory
+    repeated: bool,
+    names: HashSet<String>,
+}
+
+// rebuilds the filesystem from a transcript. anything inconsistent is
+// skipped or resolved in favour of what was seen first, with a warning.
+pub struct Replay {
+    filesystem: Filesystem,
+    current: NodeId,
+    listed: HashSet<NodeId>,
+    listing: Option<Listing>,
+    warnings: Vec<Warning>,
+}
+
+impl Replay {
+    pub fn run(transcript: &str) -> (Filesystem, Vec<Warning>) {
+        let filesystem = Filesystem::new();
+        let mut replay = Self {
+            current: filesystem.root(),
+            filesystem,
+            listed: HashSet::new(),
+            listing: None,
+            warnings: vec![],
+        };
+        for (index, line) in transcript.lines().enumerate() {
+            let number = index + 1;
+            match InputLine::try_from(line) {
+                Ok(input) => replay.apply(number, input),
+                Err(e) => replay.warn(number, format!("{}: '{}'", e, line)),
+            }
+        }
+        replay.finish_listing();
+        // a listing is only checked once it's over, after the lines below it
+        replay.warnings.sort_by_key(|warning| warning.line);
+        (replay.filesystem, replay.warnings)
+    }
+
+    fn warn(&mut self, line: usize, message: String) {
+        self.warnings.push(Warning { line, message });
+    }
+
+    fn apply(&mut self, line: usize, input: InputLine) {
+        match input {
+            InputLine::Move(dir) => {
+                self.finish_listing();
+                self.change_dir(line, dir);
+            }
+            InputLine::List => {
+                self.finish_listing();
+                self.listing = Some(Listing {
+                    line,
+                    dir: self.current,
+                    repeated: !self.listed.insert(self.current),
+                    names: HashSet::new(),
+                });
+            }
+            InputLine::Dir(name) => {
+                if self.entry(line, name) {
+                    self.add_dir(line, name);
+                }
+            }
+            InputLine::File(size, name) => {
+                if self.entry(line, name) {
+                    self.add_file(line, name, size);
+                }
+            }
+        }
+    }
+
+    fn change_dir(&mut self, line: usize, dir: Move) {
+        match dir {
+            Move::ToRoot => self.current = self.filesystem.root(),
+            Move::ToParent => match self.filesystem.node(self.current).parent {
+                Some(parent) => self.current = parent,
+                None => self.warn(line, String::from("cd .. from the root, staying put")),
+            },
+            Move::Into(name) => {
+                if let Some(child) = self.filesystem.child(self.current, name) {
+                    if self.filesystem.node(child).is_dir() {
+                        self.current = child;
+                    } else {
+                        let path = self.filesystem.path(child);
+                        self.warn(line, format!("cd into {}, which is a file", path));
+                    }
+                    return;
+                }
+                let path = self.filesystem.path(self.current);
+                let seen = if self.listed.contains(&self.current) {
+                    "isn't in the listing of"
+                } else {
+                    "was never listed in"
+                };
+                self.warn(line, format!("cd into '{}', which {} {}", name, seen, path));
+                self.current = self.filesystem.add_dir(self.current, name);
+            }
+        }
+    }
+
+    // notes an output line of `ls`, false if there is no `ls` it belongs to
+    fn entry(&mut self, line: usize, name: &str) -> bool {
+        let Some(listing) = &mut self.listing else {
+            self.warn(line, format!("'{}' listed without an ls", name));
+            return false;
+        };
+        if !listing.names.insert(name.to_owned()) {
+            self.warn(line, format!("'{}' listed twice", name));
+            return false;
+        }
+        true
+    }
+
+    fn add_dir(&mut self, line: usize, name: &str) {
+        match self.filesystem.child(self.current, name) {
+            Some(existing) if !self.filesystem.node(existing).is_dir() => {
+                let path = self.filesystem.path(existing);
+                self.warn(
+                    line,
+                    format!("{} was a file, now listed as a directory", path),
+                );
+            }
+            Some(_) => {}
+            None => {
+                self.filesystem.add_dir(self.current, name);
+            }
+        }
+    }
+
+    fn add_file(&mut self, line: usize, name: &str, size: u64) {
+        let Some(existing) = self.filesystem.child(self.current, name) else {
+            self.filesystem.add_file(self.current, name, size);
+            return;
+        };
+        let path = self.filesystem.path(existing);
+        let node = self.filesystem.node(existing);
+        if node.is_dir() {
+            self.warn(
+                line,
+                format!("{} was a directory, now listed as a file", path),
+            );
+        } else if node.size != size {
+            let message = format!(
+                "{} was {} bytes, now listed as {}, keeping {}",
+                path, node.size, size, node.size
+            );
+            self.warn(line, message);
+        }
+    }
+
+    // a second `ls` of a directory should show everything the first one did
+    fn finish_listing(&mut self) {
+        let Some(listing) = self.listing.take() else {
+            return;
+        };
+        if !listing.repeated {
+            return;
+        }
+        let missing: Vec<String> = self
+            .filesystem
+            .children(listing.dir)
+            .iter()
+            .map(|&child| &self.filesystem.node(child).name)
+            .filter(|name| !listing.names.contains(*name))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            let path = self.filesystem.path(listing.dir);
+            let message = format!(
+                "listing of {} is missing {} seen earlier",
+                path,
+                missing.join(", ")
+            );
+            self.warn(listing.line, message);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    cmp::Reverse,
+    collections::VecDeque,
+    fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::fs::{Filesystem, NodeId};
+
+// which directory the session explores next
+#[derive(Clone, Copy, Debug)]
+pub enum Order {
+    // into each directory as soon as it's listed, back out with `cd ..`
+    DepthFirst,
+    // a level at a time, walking down from the root to every directory
+    BreadthFirst,
+}
+
+// how the entries of each `ls` are arranged
+#[derive(Clone, Copy, Debug)]
+pub enum Sort {
+    // as the operating system returned them
+    Listing,
+    Name,
+    // biggest first, directories by everything inside them
+    Size,
+}
+
+// reads the directory tree under `root` from the local disk. symlinks,
+// special files and names the transcript can't hold are left out, as are
+// directories that can't be read, each with a note on stderr.
+pub fn scan(root: &Path) -> io::Result<Filesystem> {
+    let mut filesystem = Filesystem::new();
+    let top = filesystem.root();
+    scan_dir(&mut filesystem, top, root, true)?;
+    Ok(filesystem)
+}
+
+fn scan_dir(filesystem: &mut Filesystem, dir: NodeId, path: &Path, top: bool) -> io::Result<()> {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) if !top => {
+            eprintln!("skipping {}: {}", path.display(), e);
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        let name = match entry.file_name().into_string() {
+            Ok(name) if usable(&name) => name,
+            _ => {
+                eprintln!("skipping {}: name can't go in a transcript", path.display());
+                continue;
+            }
+        };
+        let metadata = match fs::symlink_metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                eprintln!("skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        if metadata.is_dir() {
+            let child = filesystem.add_dir(dir, &name);
+            scan_dir(filesystem, child, &path, false)?;
+        } else if metadata.is_file() {
+            filesystem.add_file(dir, &name, metadata.len());
+        }
+    }
+    Ok(())
+}
+
+// names that read back as the same entry
+fn usable(name: &str) -> bool {
+    !name.is_empty() && name != ".." && name != "/" && !name.contains(['\n', '\r'])
+}
+
+// a `$ cd` / `$ ls` session exploring the whole filesystem, then listing
+// `revisit` directories a second time in random order
+pub fn transcript(
+    filesystem: &Filesystem,
+    order: Order,
+    sort: Sort,
+    revisit: usize,
+    seed: Option<u64>,
+) -> String {
+    let mut session = Session {
+        filesystem,
+        sort,
+        lines: vec![String::from("$ cd /")],
+        visited: vec![],
+    };
+    let root = filesystem.root();
+    match order {
+        Order::DepthFirst => session.depth_first(root),
+        Order::BreadthFirst => {
+            let mut queue = VecDeque::from([root]);
+            while let Some(dir) = queue.pop_front() {
+                session.walk_to(dir);
+                queue.extend(session.list(dir));
+            }
+        }
+    }
+
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64)
+    });
+    let mut again = session.visited.clone();
+    shuffle(&mut again, seed);
+    for &dir in again.iter().take(revisit) {
+        session.walk_to(dir);
+        session.list(dir);
+    }
+
+    session.lines.push(String::new());
+    session.lines.join("\n")
+}
+
+struct Session<'a> {
+    filesystem: &'a Filesystem,
+    sort: Sort,
+    lines: Vec<String>,
+    visited: Vec<NodeId>,
+}
+
+impl Session<'_> {
+    fn depth_first(&mut self, dir: NodeId) {
+        for child in self.list(dir) {
+            let name = &self.filesystem.node(child).name;
+            self.lines.push(format!("$ cd {}", name));
+            self.depth_first(child);
+            self.lines.push(String::from("$ cd .."));
+        }
+    }
+
+    // `cd` from the root down to `dir`
+    fn walk_to(&mut self, dir: NodeId) {
+        let mut names = vec![];
+        let mut current = dir;
+        while let Some(parent) = self.filesystem.node(current).parent {
+            names.push(&self.filesystem.node(current).name);
+            current = parent;
+        }
+        self.lines.push(String::from("$ cd /"));
+        for name in names.iter().rev() {
+            self.lines.push(format!("$ cd {}", name));
+        }
+    }
+
+    // prints an `ls` of `dir`, returning the directories in it in the same order
+    fn list(&mut self, dir: NodeId) -> Vec<NodeId> {
+        self.visited.push(dir);
+        let mut children = self.filesystem.children(dir).to_vec();
+        match self.sort {
+            Sort::Listing => {}
+            Sort::Name => children.sort_by(|a, b| {
+                self.filesystem
+                    .node(*a)
+                    .name
+                    .cmp(&self.filesystem.node(*b).name)
+            }),
+            Sort::Size => children.sort_by_key(|&child| Reverse(self.filesystem.size(child))),
+        }
+        self.lines.push(String::from("$ ls"));
+        for &child in &children {
+            let node = self.filesystem.node(child);
+            if node.is_dir() {
+                self.lines.push(format!("dir {}", node.name));
+            } else {
+                self.lines.push(format!("{} {}", node.size, node.name));
+            }
+        }
+        children
+            .into_iter()
+            .filter(|&child| self.filesystem.node(child).is_dir())
+            .collect()
+    }
+}
+
+// fisher-yates, stepping a linear congruential generator from `seed` to pick
+// each swap. the high bits are the random ones, so those choose the index.
+fn shuffle<T>(items: &mut [T], seed: u64) {
+    let mut state = seed;
+    for i in (1..items.len()).rev() {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        items.swap(i, (state >> 33) as usize % (i + 1));
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+
+// a value for every tree, stored row by row
+#[derive(Clone, Debug)]
+pub struct Grid<T> {
+    pub rows: usize,
+    pub cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    fn new(rows: usize, cols: usize, value: T) -> Self {
+        Self {
+            rows,
+            cols,
+            cells: vec![value; rows * cols],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn get(&self, row: usize, col: usize) -> &T {
+        &self.cells[row * self.cols + col]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self
+            .cells
+            .iter()
+            .map(|c| c.to_string().len())
+            .max()
+            .unwrap_or(0);
+        for row in self.cells.chunks(self.cols.max(1)) {
+            let cells: Vec<String> = row.iter().map(|c| format!("{:>width$}", c)).collect();
+            writeln!(f, "{}", cells.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+// the tree heights, one digit per tree
+pub fn parse(input: &str) -> Result<Grid<u8>, String> {
+    let mut cells = vec![];
+    let mut cols = None;
+    let mut rows = 0;
+    for (index, line) in input.lines().enumerate() {
+        if !line.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("line {}: heights must be digits", index + 1));
+        }
+        if *cols.get_or_insert(line.len()) != line.len() {
+            return Err(format!(
+                "line {}: expected {} trees, found {}",
+                index + 1,
+                cols.unwrap(),
+                line.len()
+            ));
+        }
+        cells.extend(line.bytes().map(|b| b - b'0'));
+        rows += 1;
+    }
+    Ok(Grid {
+        rows,
+        cols: cols.unwrap_or(0),
+        cells,
+    })
+}
+
+// the way a tree looks
+#[derive(Clone, Copy, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+}
+
+pub struct Analysis {
+    // seen from outside the forest in at least one direction
+    pub visible: Grid<bool>,
+    // how many trees each tree can see looking each way, indexed by `Direction`
+    pub distances: [Grid<u32>; 4],
+    pub scenic: Grid<u64>,
+}
+
+impl Analysis {
+    // one sweep per direction, each tree pushed and popped once per sweep
+    pub fn new(heights: &Grid<u8>) -> Self {
+        let (rows, cols) = (heights.rows, heights.cols);
+        let mut visible = Grid::new(rows, cols, false);
+        let mut distances: [Grid<u32>; 4] = std::array::from_fn(|_| Grid::new(rows, cols, 0));
+
+        for direction in Direction::ALL {
+            let distance = &mut distances[direction as usize];
+            // each line of trees, walked from the edge the trees are looking at
+            let lines: Vec<Vec<usize>> = match direction {
+                Direction::Left => (0..rows)
+                    .map(|r| (0..cols).map(|c| r * cols + c).collect())
+                    .collect(),
+                Direction::Right => (0..rows)
+                    .map(|r| (0..cols).rev().map(|c| r * cols + c).collect())
+                    .collect(),
+                Direction::Up => (0..cols)
+                    .map(|c| (0..rows).map(|r| r * cols + c).collect())
+                    .collect(),
+                Direction::Down => (0..cols)
+                    .map(|c| (0..rows).rev().map(|r| r * cols + c).collect())
+                    .collect(),
+            };
+            for line in lines {
+                sweep(heights, &line, &mut visible, distance);
+            }
+        }
+
+        let mut scenic = Grid::new(rows, cols, 1u64);
+        for (index, score) in scenic.cells.iter_mut().enumerate() {
+            for distance in &distances {
+                *score *= distance.cells[index] as u64;
+            }
+        }
+
+        Self {
+            visible,
+            distances,
+            scenic,
+        }
+    }
+}
+
+// walks a line away from the edge, keeping the trees not yet blocked by a
+// taller or equal one. whatever is left below a tree is the nearest tree
+// blocking its view back, and with nothing left it can see out.
+fn sweep(heights: &Grid<u8>, line: &[usize], visible: &mut Grid<bool>, distance: &mut Grid<u32>) {
+    let mut stack: Vec<usize> = vec![];
+    for (position, &cell) in line.iter().enumerate() {
+        let height = heights.cells[cell];
+        while stack
+            .last()
+            .is_some_and(|&p| heights.cells[line[p]] < height)
+        {
+            stack.pop();
+        }
+        match stack.last() {
+            Some(&blocker) => distance.cells[cell] = (position - blocker) as u32,
+            None => {
+                distance.cells[cell] = position as u32;
+                visible.cells[cell] = true;
+            }
+        }
+        stack.push(position);
+    }
+}